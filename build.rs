@@ -0,0 +1,8 @@
+fn main() {
+    // Target triple the binary is built for, used to pick release archives
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        std::env::var("TARGET").expect("cargo should set TARGET")
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,491 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::{NamedTempFile, TempPath};
+
+use crate::{
+    config::Settings,
+    constants::TRUSTBLOCK_API_KEY_HEADER,
+    error::TrustblockError,
+    ipfs::file_cid,
+    journal::PublishJournal,
+    retry::{RetryPolicy, IDEMPOTENCY_KEY_HEADER},
+    store::ReportStore,
+    types::{Audit, Chains, Project},
+    utils::validate_pdf,
+};
+
+/// Where the audit report comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportSource {
+    Pdf(PathBuf),
+    Url(String),
+}
+
+/// Result of [`TrustblockClient::publish_audit`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PublishedAudit {
+    pub audit_id: Option<String>,
+    pub project_id: Option<String>,
+    pub report_cid: String,
+    pub report_url: String,
+    pub warnings: Vec<String>,
+}
+
+/// Client of the Trustblock API.
+///
+/// Holds the settings of a profile, the API key, the report store and a
+/// connection pool shared by every request, so it is built once and reused:
+///
+/// ```no_run
+/// # async fn publish(audit: trustblock_cli::types::Audit) -> eyre::Result<()> {
+/// use trustblock_cli::client::{ReportSource, TrustblockClient};
+///
+/// let client = TrustblockClient::from_env(None)?;
+///
+/// let published = client
+///     .publish_audit(audit, ReportSource::Pdf("report.pdf".into()))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TrustblockClient {
+    http: Client,
+    transfer: Client,
+    settings: Settings,
+    api_key: String,
+    store: Arc<dyn ReportStore>,
+}
+
+impl TrustblockClient {
+    /// Builds a client, with an HTTP client configured by the HTTP settings
+    /// of the profile.
+    pub fn new(settings: Settings, api_key: impl Into<String>) -> eyre::Result<Self> {
+        let transfer = settings.http.transfer_client()?;
+
+        Ok(
+            Self::with_http_client(settings.http.client()?, settings, api_key)?
+                .with_transfer_client(transfer),
+        )
+    }
+
+    /// Builds a client sending requests, uploads and downloads with an
+    /// existing HTTP client.
+    ///
+    /// The report store is built from the `report_store` setting of the
+    /// profile, see [`crate::store::StoreSettings::store`].
+    pub fn with_http_client(
+        http: Client,
+        settings: Settings,
+        api_key: impl Into<String>,
+    ) -> eyre::Result<Self> {
+        Ok(Self {
+            transfer: http.clone(),
+            http,
+            store: settings.store.store()?.into(),
+            settings,
+            api_key: api_key.into(),
+        })
+    }
+
+    /// Builds a client from the selected profile, with the API key given or
+    /// resolved from the profile, see [`Settings::api_key`].
+    pub fn from_env(api_key: Option<String>) -> eyre::Result<Self> {
+        let settings = Settings::load()?;
+        let api_key = settings.api_key(api_key)?;
+
+        Self::new(settings, api_key)
+    }
+
+    /// Replaces the retry policy of the profile.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.settings.retry = retry;
+        self
+    }
+
+    /// Replaces the HTTP client of uploads and downloads, which should not
+    /// limit the duration of a request.
+    #[must_use]
+    pub fn with_transfer_client(mut self, transfer: Client) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Replaces the report store of the profile.
+    #[must_use]
+    pub fn with_report_store(mut self, store: impl ReportStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    #[must_use]
+    pub const fn http(&self) -> &Client {
+        &self.http
+    }
+
+    /// HTTP client of uploads and downloads, see
+    /// [`crate::http::HttpSettings::transfer_client`].
+    #[must_use]
+    pub const fn transfer_http(&self) -> &Client {
+        &self.transfer
+    }
+
+    #[must_use]
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    #[must_use]
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    #[must_use]
+    pub fn report_store(&self) -> &dyn ReportStore {
+        self.store.as_ref()
+    }
+
+    /// Returns the auditor the API key belongs to.
+    pub async fn get_identity(&self) -> eyre::Result<Value> {
+        let response = self
+            .settings
+            .retry
+            .send(true, || {
+                self.http
+                    .get(format!("{}me", self.settings.auth_endpoint))
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TrustblockError::from_response(response).await.into());
+        }
+
+        Ok(response.json::<Value>().await?)
+    }
+
+    /// Returns the id of the project with the given slug, or `None` if it does
+    /// not exist yet.
+    pub async fn get_project_by_slug(&self, slug: &str) -> eyre::Result<Option<String>> {
+        let response = self
+            .settings
+            .retry
+            .send(true, || {
+                self.http
+                    .get(format!("{}{slug}", self.settings.project_slug_endpoint))
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+            })
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            StatusCode::OK => {
+                let project_response_data = response.json::<Value>().await?;
+                let project_id = project_response_data["id"].to_string().replace('\"', "");
+
+                Ok(Some(project_id))
+            }
+            _ => Err(
+                eyre::Report::new(TrustblockError::from_response(response).await)
+                    .wrap_err("Error occurred while fetching a project id"),
+            ),
+        }
+    }
+
+    /// Checks whether an audit with the given report CID is already published.
+    ///
+    /// Statuses other than 200 and 404 are errors, which callers can treat as
+    /// unknown: the check only saves a duplicate upload.
+    pub async fn report_exists(&self, report_cid: &str) -> eyre::Result<bool> {
+        let url = format!("{}{report_cid}", self.settings.audit_report_hash_endpoint);
+
+        let response = self
+            .settings
+            .retry
+            .send(true, || {
+                self.http
+                    .get(&url)
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+            })
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(
+                eyre::Report::new(TrustblockError::from_response(response).await)
+                    .wrap_err("Could not check whether the report is already published"),
+            ),
+        }
+    }
+
+    /// Requests a short lived web3.storage token to upload a report with.
+    ///
+    /// The request is retried: a token that is issued twice is only unused.
+    pub async fn request_upload_token(&self) -> eyre::Result<String> {
+        let response = self
+            .settings
+            .retry
+            .send(true, || {
+                self.http
+                    .post(&self.settings.web3_storage_api_endpoint)
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(
+                eyre::Report::new(TrustblockError::from_response(response).await)
+                    .wrap_err("Could not request a report upload token"),
+            );
+        }
+
+        let api_response_data = response.json::<Value>().await?;
+
+        let token = api_response_data["apiKey"]
+            .to_string()
+            .trim()
+            .replace('\"', "");
+
+        if token.is_empty() || token == "null" {
+            return Err(eyre::eyre!(
+                "Could not request a report upload token: the response has no apiKey"
+            ));
+        }
+
+        Ok(token)
+    }
+
+    /// Renders a web based report to a PDF and returns the temporary file it
+    /// is written to, which is removed when dropped.
+    pub async fn render_pdf(&self, url: &str) -> eyre::Result<TempPath> {
+        let temp_pdf_file = NamedTempFile::new()?;
+
+        let response = self
+            .settings
+            .retry
+            .send(true, || {
+                self.transfer
+                    .get(&self.settings.pdf_generate_endpoint)
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+                    .query(&[("url", url)])
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(
+                eyre::Report::new(TrustblockError::from_response(response).await)
+                    .wrap_err("Could not fetch web based audit report"),
+            );
+        }
+
+        let response_bytes = response.bytes().await?;
+
+        let temp_pdf_path = temp_pdf_file.path();
+
+        std::fs::write(temp_pdf_path, response_bytes)?;
+
+        validate_pdf(temp_pdf_path.to_str().expect("should not fail"))?;
+
+        Ok(temp_pdf_file.into_temp_path())
+    }
+
+    /// Uploads the report to the store of the client and returns its CID and
+    /// public URL.
+    #[allow(clippy::future_not_send)]
+    pub async fn upload_report(&self, report_file_path: &Path) -> eyre::Result<(String, String)> {
+        self.store.upload(self, report_file_path).await
+    }
+
+    /// Creates an audit from a payload built with [`build_audit`] and returns
+    /// its id.
+    ///
+    /// The request is sent once, unless `audit_idempotency_key` is enabled for
+    /// an API that honors [`IDEMPOTENCY_KEY_HEADER`]: the request then carries
+    /// a key derived from the payload and is retried.
+    pub async fn create_audit(&self, audit: &Audit) -> eyre::Result<Option<String>> {
+        let idempotency_key = if self.settings.audit_idempotency_key {
+            Some(format!("{:x}", Sha256::digest(serde_json::to_vec(audit)?)))
+        } else {
+            None
+        };
+
+        let response = self
+            .settings
+            .retry
+            .send(idempotency_key.is_some(), || {
+                let request = self
+                    .http
+                    .post(&self.settings.audit_endpoint)
+                    .header(TRUSTBLOCK_API_KEY_HEADER, &self.api_key)
+                    .json(audit);
+
+                match &idempotency_key {
+                    Some(idempotency_key) => {
+                        request.header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+                    }
+                    None => request,
+                }
+            })
+            .await?;
+
+        if response.status() == StatusCode::CREATED {
+            let body = response.json::<Value>().await.unwrap_or_default();
+
+            return Ok(body["id"].as_str().map(ToString::to_string));
+        }
+
+        match TrustblockError::from_response(response).await {
+            error @ (TrustblockError::DuplicateReport | TrustblockError::DuplicateProject) => {
+                Err(error.into())
+            }
+            error => Err(eyre::Report::new(error).wrap_err("Could not publish to DB")),
+        }
+    }
+
+    /// Publishes validated audit data: resolves the project, renders and
+    /// uploads the report, and creates the audit.
+    #[allow(clippy::future_not_send)]
+    pub async fn publish_audit(
+        &self,
+        audit_data: Audit,
+        report: ReportSource,
+    ) -> eyre::Result<PublishedAudit> {
+        self.publish_audit_with_journal(audit_data, report, &mut PublishJournal::default())
+            .await
+    }
+
+    /// Same as [`TrustblockClient::publish_audit`], recording each completed
+    /// step in the journal and skipping steps already recorded there.
+    #[allow(clippy::future_not_send)]
+    pub async fn publish_audit_with_journal(
+        &self,
+        audit_data: Audit,
+        report: ReportSource,
+        journal: &mut PublishJournal,
+    ) -> eyre::Result<PublishedAudit> {
+        let mut warnings = self.settings.warnings();
+
+        if !journal.project_resolved {
+            journal.project_id = self
+                .get_project_by_slug(&audit_data.project.slug()?)
+                .await?;
+            journal.project_resolved = true;
+            journal.save()?;
+        }
+
+        let project_id = journal.project_id.clone();
+
+        let (report_cid, report_url) = if let Some(uploaded) = journal.uploaded_report() {
+            warnings.push("Report was uploaded by a previous run, skipping upload".to_string());
+            uploaded
+        } else {
+            // Keeps a rendered report until it is uploaded, and removes it on
+            // every path
+            let rendered_pdf;
+
+            let report_pdf_file_path = match report {
+                ReportSource::Pdf(path) => path,
+                ReportSource::Url(url) => {
+                    rendered_pdf = self.render_pdf(&url).await?;
+                    rendered_pdf.to_path_buf()
+                }
+            };
+
+            let local_cid = file_cid(&report_pdf_file_path)?;
+
+            match self.report_exists(&local_cid).await {
+                Ok(true) => {
+                    journal.remove()?;
+                    return Err(TrustblockError::DuplicateReport.into());
+                }
+                Ok(false) => (),
+                Err(error) => warnings.push(format!("{error:#}, uploading the report anyway")),
+            }
+
+            let uploaded = self.upload_report(&report_pdf_file_path).await?;
+
+            if uploaded.0 != local_cid {
+                warnings.push(format!(
+                    "Uploaded report CID {} differs from the locally computed CID {local_cid}",
+                    uploaded.0
+                ));
+            }
+
+            journal.report_cid = Some(uploaded.0.clone());
+            journal.report_url = Some(uploaded.1.clone());
+            journal.save()?;
+
+            uploaded
+        };
+
+        let audit_data_send = build_audit(
+            audit_data,
+            project_id.clone(),
+            report_cid.clone(),
+            report_url.clone(),
+        );
+
+        let audit_id = match self.create_audit(&audit_data_send).await {
+            Ok(audit_id) => audit_id,
+            Err(error) => {
+                if matches!(
+                    error.downcast_ref::<TrustblockError>(),
+                    Some(TrustblockError::DuplicateReport | TrustblockError::DuplicateProject)
+                ) {
+                    journal.remove()?;
+                }
+
+                return Err(error);
+            }
+        };
+
+        journal.remove()?;
+
+        Ok(PublishedAudit {
+            audit_id,
+            project_id,
+            report_cid,
+            report_url,
+            warnings,
+        })
+    }
+}
+
+/// Builds the payload sent to the audit endpoint from the parsed audit data.
+#[must_use]
+pub fn build_audit(
+    audit_data: Audit,
+    project_id: Option<String>,
+    report_hash: String,
+    report_file_url: String,
+) -> Audit {
+    let chains = audit_data
+        .contracts
+        .iter()
+        .map(|contract| contract.chain)
+        .unique()
+        .collect::<Vec<Chains>>();
+
+    let project = Project {
+        id: project_id,
+        ..audit_data.clone().project
+    };
+
+    Audit {
+        chains,
+        report_hash,
+        report_file_url,
+        project,
+        ..audit_data
+    }
+}
@@ -1,26 +1,26 @@
 use clap::Parser;
 use eyre::ContextCompat;
 
-use crate::{cmd::utils::Cmd, constants::CLI_PATH};
+use crate::{cmd::utils::Cmd, constants::CLI_PATH, sh_println, shell::CommandOutput};
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Parser)]
 pub struct CleanArgs {}
 
 impl Cmd for CleanArgs {
-    fn run(self) -> eyre::Result<()> {
+    fn run(self) -> eyre::Result<CommandOutput> {
         let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
         let trustblock_dir = home_dir.join(CLI_PATH);
 
         if !trustblock_dir.exists() {
-            println!("No .trustblock folder found");
-            return Ok(());
+            sh_println!("No .trustblock folder found");
+            return Ok(CommandOutput::new("clean"));
         }
 
         std::fs::remove_dir_all(trustblock_dir)?;
 
-        println!("Cleaned .trustblock folder");
+        sh_println!("Cleaned .trustblock folder");
 
-        Ok(())
+        Ok(CommandOutput::new("clean"))
     }
 }
@@ -0,0 +1,259 @@
+use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use eyre::eyre;
+
+use crate::{
+    cmd::utils::Cmd,
+    config::{
+        self, dotenv_values, remove_dotenv_var, selected_profile, ConfigFile, Kind, Layers, Source,
+        KEYS,
+    },
+    sh_println,
+    shell::{CommandOutput, SettingOutput},
+    utils::apply_dotenv,
+    vault,
+};
+
+/// Top level key selecting the default profile, the other keys belong to a
+/// profile.
+const DEFAULT_PROFILE_KEY: &str = "default_profile";
+
+fn key_parser() -> PossibleValuesParser {
+    PossibleValuesParser::new(KEYS.iter().map(|key| key.name).chain([DEFAULT_PROFILE_KEY]))
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum ConfigCommands {
+    #[command(
+        about = "Prints a setting of the selected profile and where it comes from",
+        long_about = "Prints a setting of the selected profile and where it comes from. Secrets, like the API key, are masked unless --reveal is passed"
+    )]
+    Get {
+        #[arg(value_parser = key_parser())]
+        key: String,
+
+        #[arg(long, help = "Prints secrets in full")]
+        reveal: bool,
+    },
+
+    #[command(
+        about = "Sets a setting of the selected profile in the config file",
+        long_about = "Sets a setting of the selected profile in the config file. Secrets, like the API key, are encrypted with a passphrase, read from TRUSTBLOCK_PASSPHRASE or prompted"
+    )]
+    Set {
+        #[arg(value_parser = key_parser())]
+        key: String,
+        value: String,
+    },
+
+    #[command(about = "Removes a setting of the selected profile from the config file")]
+    Unset {
+        #[arg(value_parser = key_parser())]
+        key: String,
+    },
+
+    #[command(about = "Lists the settings of the selected profile, with secrets masked")]
+    List,
+
+    #[command(
+        about = "Encrypts the plain text API key of the selected profile or ~/.trustblock/.env",
+        long_about = "Encrypts the plain text API key of the selected profile, or the API_KEY of ~/.trustblock/.env, with a passphrase. The encrypted key is stored in the selected profile and the plain text key is removed"
+    )]
+    Encrypt,
+}
+
+impl Cmd for ConfigArgs {
+    fn run(self) -> eyre::Result<CommandOutput> {
+        let mut output = CommandOutput::new("config");
+
+        match self.command {
+            ConfigCommands::Get { key, reveal } => {
+                let setting = lookup(&key, reveal)?;
+
+                match (&setting.value, &setting.source) {
+                    (Some(value), Some(source)) => sh_println!("{key} = {value} ({source})"),
+                    _ => sh_println!("{key} is not set"),
+                }
+
+                output.settings.push(setting);
+            }
+            ConfigCommands::Set { key, value } => {
+                let kind = config::key(&key).map(|key| key.kind);
+
+                if kind == Some(Kind::Number) && value.parse::<u64>().is_err() {
+                    return Err(eyre!("{key} must be a whole number, got {value:?}"));
+                }
+
+                if kind == Some(Kind::Boolean) && value.parse::<bool>().is_err() {
+                    return Err(eyre!("{key} must be true or false, got {value:?}"));
+                }
+
+                let profile = target_profile(&key)?;
+
+                let value = if kind == Some(Kind::Secret) && !vault::is_encrypted(&value) {
+                    vault::encrypt(&value, &vault::new_passphrase()?)?
+                } else {
+                    value
+                };
+
+                let path = ConfigFile::set_value(profile.as_deref(), &key, &value)?;
+
+                sh_println!(
+                    "Set {key} in {}",
+                    describe_target(profile.as_deref(), &path)
+                );
+
+                if profile.is_some() {
+                    if let Some((_, source @ Source::Env(_))) = Layers::load()?.lookup(&key) {
+                        output.warn(format!("{key} is overridden by {source}"));
+                    }
+                }
+
+                output.settings.push(SettingOutput {
+                    value: Some(mask(&key, &value)),
+                    key,
+                    source: None,
+                });
+            }
+            ConfigCommands::Unset { key } => {
+                let profile = target_profile(&key)?;
+
+                let path = ConfigFile::unset_value(profile.as_deref(), &key)?;
+
+                sh_println!(
+                    "Unset {key} in {}",
+                    describe_target(profile.as_deref(), &path)
+                );
+
+                output.settings.push(lookup(&key, false)?);
+            }
+            ConfigCommands::List => {
+                let layers = Layers::load()?;
+
+                sh_println!("Profile: {} ({})\n", layers.profile, layers.profile_source);
+
+                output.settings = KEYS
+                    .iter()
+                    .map(|key| {
+                        let (value, source) = layers.lookup(key.name).unzip();
+
+                        SettingOutput {
+                            key: key.name.to_string(),
+                            value: value.map(|value| mask(key.name, &value)),
+                            source: source.map(|source| source.to_string()),
+                        }
+                    })
+                    .collect();
+
+                let key_width = KEYS
+                    .iter()
+                    .map(|key| key.name.len())
+                    .max()
+                    .unwrap_or_default();
+
+                for setting in &output.settings {
+                    sh_println!(
+                        "{:key_width$}  {}  ({})",
+                        setting.key,
+                        setting.value.as_deref().unwrap_or("-"),
+                        setting.source.as_deref().unwrap_or("not set")
+                    );
+                }
+            }
+            ConfigCommands::Encrypt => {
+                let profile = target_profile("api_key")?.unwrap_or_default();
+
+                let profile_api_key = ConfigFile::load()?
+                    .profiles
+                    .get(&profile)
+                    .and_then(|settings| settings.api_key.clone())
+                    .filter(|api_key| !vault::is_encrypted(api_key));
+
+                let dotenv_api_key = dotenv_values()
+                    .remove("API_KEY")
+                    .filter(|api_key| !api_key.is_empty());
+
+                let Some(api_key) = profile_api_key.or_else(|| dotenv_api_key.clone()) else {
+                    sh_println!("No plain text API key to encrypt");
+                    return Ok(output);
+                };
+
+                let encrypted_api_key = vault::encrypt(&api_key, &vault::new_passphrase()?)?;
+
+                let path = ConfigFile::set_value(Some(&profile), "api_key", &encrypted_api_key)?;
+
+                if dotenv_api_key.as_ref() == Some(&api_key) {
+                    remove_dotenv_var("API_KEY")?;
+                }
+
+                sh_println!("Stored encrypted API key in profile {profile} of {path:?}");
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Returns the profile a key is written to, `None` for `default_profile`.
+fn target_profile(key: &str) -> eyre::Result<Option<String>> {
+    if key == DEFAULT_PROFILE_KEY {
+        return Ok(None);
+    }
+
+    apply_dotenv()?;
+
+    Ok(Some(selected_profile(&ConfigFile::load()?).0))
+}
+
+fn describe_target(profile: Option<&str>, path: &std::path::Path) -> String {
+    match profile {
+        Some(profile) => format!("profile {profile} of {path:?}"),
+        None => format!("{path:?}"),
+    }
+}
+
+/// Resolves a key of the selected profile, or `default_profile`. Secrets are
+/// masked unless `reveal` is set.
+fn lookup(key: &str, reveal: bool) -> eyre::Result<SettingOutput> {
+    let (value, source) = if key == DEFAULT_PROFILE_KEY {
+        let value = ConfigFile::load()?.default_profile;
+        let source = value.as_ref().map(|_| "config.toml".to_string());
+
+        (value, source)
+    } else {
+        let (value, source) = Layers::load()?.lookup(key).unzip();
+
+        (value, source.map(|source| source.to_string()))
+    };
+
+    Ok(SettingOutput {
+        key: key.to_string(),
+        value: value.map(|value| if reveal { value } else { mask(key, &value) }),
+        source,
+    })
+}
+
+/// Hides all but the last 4 characters of plain text secrets.
+fn mask(key: &str, value: &str) -> String {
+    if config::key(key).map(|key| key.kind) != Some(Kind::Secret) {
+        return value.to_string();
+    }
+
+    if vault::is_encrypted(value) {
+        return "<encrypted>".to_string();
+    }
+
+    let visible = if value.chars().count() > 8 {
+        value.chars().skip(value.chars().count() - 4).collect()
+    } else {
+        String::new()
+    };
+
+    format!("****{visible}")
+}
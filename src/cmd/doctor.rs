@@ -0,0 +1,338 @@
+use std::time::Duration;
+
+use clap::Parser;
+use eyre::ContextCompat;
+use reqwest::Client;
+
+use crate::{
+    client::TrustblockClient,
+    cmd::whoami::describe_identity,
+    config::{Layers, Settings},
+    constants::CLI_PATH,
+    error::{ExitCode, TrustblockError},
+    http::HttpSettings,
+    sh_println,
+    shell::{CheckOutput, CheckStatus, CommandOutput, ResultStatus},
+    vault,
+};
+
+/// Timeout of each endpoint probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct DoctorArgs {
+    #[clap(short = 'k', long)]
+    api_key: Option<String>,
+}
+
+impl DoctorArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let mut output = CommandOutput::new("doctor");
+        let mut checks = Vec::new();
+
+        let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
+        let env_path = home_dir.join(CLI_PATH).join(".env");
+
+        checks.push(if env_path.exists() {
+            check("env file", CheckStatus::Pass, format!("{env_path:?}"), None)
+        } else {
+            check(
+                "env file",
+                CheckStatus::Warn,
+                format!("{env_path:?} not found"),
+                Some("Run `trustblock init` if you configure settings with environment variables"),
+            )
+        });
+
+        let layers = match Layers::load() {
+            Ok(layers) => {
+                checks.push(check(
+                    "profile",
+                    CheckStatus::Pass,
+                    format!("{} ({})", layers.profile, layers.profile_source),
+                    None,
+                ));
+                layers
+            }
+            Err(error) => {
+                checks.push(check(
+                    "profile",
+                    CheckStatus::Fail,
+                    format!("{error:#}"),
+                    Some("Fix ~/.trustblock/config.toml or select another profile with --profile"),
+                ));
+                return Ok(finish(output, checks));
+            }
+        };
+
+        let settings = match Settings::from_layers(&layers) {
+            Ok(settings) => settings,
+            Err(error) => {
+                checks.push(check(
+                    "settings",
+                    CheckStatus::Fail,
+                    format!("{error:#}"),
+                    Some("Fix the value with trustblock config set or unset it"),
+                ));
+                return Ok(finish(output, checks));
+            }
+        };
+        let http_settings = HttpSettings {
+            timeout: settings.http.timeout.min(PROBE_TIMEOUT),
+            ..settings.http.clone()
+        };
+
+        let client = match http_settings.client() {
+            Ok(client) => {
+                checks.push(http_check(&settings.http));
+                client
+            }
+            Err(error) => {
+                checks.push(check(
+                    "http client",
+                    CheckStatus::Fail,
+                    format!("{error:#}"),
+                    Some("Check proxy_url and ca_bundle with `trustblock config list`"),
+                ));
+                return Ok(finish(output, checks));
+            }
+        };
+
+        let api_key = match settings.api_key(self.api_key) {
+            Ok(api_key) => {
+                let detail = match &settings.api_key_source {
+                    Some(source)
+                        if settings.api_key.as_deref().is_some_and(vault::is_encrypted) =>
+                    {
+                        format!("encrypted, from {source}")
+                    }
+                    Some(source) => format!("plain text, from {source}"),
+                    None => "from --api-key".to_string(),
+                };
+
+                checks.push(if settings.api_key_in_plain_text() {
+                    check(
+                        "API key",
+                        CheckStatus::Warn,
+                        detail,
+                        Some("Run `trustblock config encrypt`"),
+                    )
+                } else {
+                    check("API key", CheckStatus::Pass, detail, None)
+                });
+                Some(api_key)
+            }
+            Err(error) => {
+                checks.push(check(
+                    "API key",
+                    CheckStatus::Fail,
+                    format!("{error:#}"),
+                    Some("Run `trustblock login` or `trustblock config set api_key <API_KEY>`"),
+                ));
+                None
+            }
+        };
+
+        let trustblock_client = match TrustblockClient::with_http_client(
+            client.clone(),
+            settings.clone(),
+            api_key.clone().unwrap_or_default(),
+        ) {
+            Ok(trustblock_client) => {
+                checks.push(check(
+                    "report store",
+                    CheckStatus::Pass,
+                    trustblock_client.report_store().name().to_string(),
+                    None,
+                ));
+                Some(trustblock_client)
+            }
+            Err(error) => {
+                checks.push(check(
+                    "report store",
+                    CheckStatus::Fail,
+                    format!("{error:#}"),
+                    Some("Check the report store settings with `trustblock config list`"),
+                ));
+                None
+            }
+        };
+
+        if let (Some(_), Some(trustblock_client)) = (&api_key, &trustblock_client) {
+            checks.push(match trustblock_client.get_identity().await {
+                Ok(identity) => {
+                    let detail = describe_identity(&identity);
+                    output.identity = Some(identity);
+                    check("authentication", CheckStatus::Pass, detail, None)
+                }
+                Err(error) => {
+                    let fix = match error.downcast_ref::<TrustblockError>() {
+                        Some(TrustblockError::Unauthorized { .. }) => {
+                            "The API key is invalid or revoked. Run `trustblock login` again"
+                        }
+                        _ => "Check auth_endpoint with `trustblock config get auth_endpoint`",
+                    };
+
+                    let detail = error
+                        .downcast_ref::<reqwest::Error>()
+                        .map_or_else(|| format!("{error:#}"), describe_request_error);
+
+                    check("authentication", CheckStatus::Fail, detail, Some(fix))
+                }
+            });
+        }
+
+        for (name, key, url) in [
+            ("audit endpoint", "audit_endpoint", &settings.audit_endpoint),
+            (
+                "report hash endpoint",
+                "audit_report_hash_endpoint",
+                &settings.audit_report_hash_endpoint,
+            ),
+            (
+                "project endpoint",
+                "project_slug_endpoint",
+                &settings.project_slug_endpoint,
+            ),
+            (
+                "file endpoint",
+                "web3_storage_api_endpoint",
+                &settings.web3_storage_api_endpoint,
+            ),
+            (
+                "pdf endpoint",
+                "pdf_generate_endpoint",
+                &settings.pdf_generate_endpoint,
+            ),
+        ] {
+            let source = layers
+                .lookup(key)
+                .map(|(_, source)| source.to_string())
+                .unwrap_or_default();
+
+            checks.push(match probe(&client, url).await {
+                Ok(status) => check(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{url} responded {status} ({source})"),
+                    None,
+                ),
+                Err(error) => check(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{url} ({source}): {error}"),
+                    Some(&format!(
+                        "Check {key} with `trustblock config get {key}`, or retry later if the service is down"
+                    )),
+                ),
+            });
+        }
+
+        Ok(finish(output, checks))
+    }
+}
+
+/// Describes the proxy, CA bundle and TLS settings requests are sent with.
+fn http_check(http: &HttpSettings) -> CheckOutput {
+    let proxy = http.proxy_url.clone().unwrap_or_else(|| {
+        ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+            .into_iter()
+            .find_map(|var| std::env::var(var).ok())
+            .map_or_else(|| "none".to_string(), |proxy| format!("{proxy} (env)"))
+    });
+
+    let ca_bundle = http
+        .ca_bundle
+        .as_ref()
+        .map_or_else(|| "none".to_string(), |path| format!("{path:?}"));
+
+    let detail = format!("proxy {proxy}, CA bundle {ca_bundle}");
+
+    if http.insecure {
+        check(
+            "http client",
+            CheckStatus::Warn,
+            format!("{detail}, TLS certificate verification disabled"),
+            Some("Unset insecure outside of local testing"),
+        )
+    } else {
+        check("http client", CheckStatus::Pass, detail, None)
+    }
+}
+
+/// Checks that an endpoint is reachable: any response below 500 means the
+/// service is up, even if the bare endpoint URL is not a valid request.
+async fn probe(client: &Client, url: &str) -> Result<u16, String> {
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_server_error() => {
+            Err(format!("server error {}", response.status()))
+        }
+        Ok(response) => Ok(response.status().as_u16()),
+        Err(error) => Err(describe_request_error(&error)),
+    }
+}
+
+fn describe_request_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        "timed out".to_string()
+    } else if error.is_connect() {
+        "connection failed".to_string()
+    } else {
+        error.to_string()
+    }
+}
+
+fn check(name: &str, status: CheckStatus, detail: String, fix: Option<&str>) -> CheckOutput {
+    CheckOutput {
+        name: name.to_string(),
+        status,
+        detail,
+        fix: fix.map(ToString::to_string),
+    }
+}
+
+/// Prints the check table and fails the command if any check failed.
+fn finish(mut output: CommandOutput, checks: Vec<CheckOutput>) -> CommandOutput {
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .chain([5])
+        .max()
+        .unwrap_or_default();
+
+    sh_println!("{:name_width$}  STATUS  DETAIL", "CHECK");
+
+    for check in &checks {
+        let status = match check.status {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        };
+
+        sh_println!("{:name_width$}  {status:6}  {}", check.name, check.detail);
+
+        if let Some(fix) = &check.fix {
+            sh_println!("{:name_width$}          fix: {fix}", "");
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Fail)
+        .count();
+
+    if failed > 0 {
+        sh_println!("\n{failed} checks failed\n");
+
+        output.status = ResultStatus::Error;
+        output.error = Some(format!("{failed} checks failed"));
+        output.exit_code = Some(ExitCode::Failure as i32);
+    } else {
+        sh_println!("\nAll checks passed\n");
+    }
+
+    output.checks = checks;
+    output
+}
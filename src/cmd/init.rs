@@ -1,9 +1,20 @@
-use std::{fs::File, io::prelude::Write};
-
 use clap::Parser;
 use eyre::ContextCompat;
 
-use crate::{cmd::utils::Cmd, constants::CLI_PATH};
+use crate::{
+    cmd::utils::Cmd,
+    config::{selected_profile, ConfigFile},
+    constants::CLI_PATH,
+    sh_println,
+    shell::CommandOutput,
+    vault::{self, write_private},
+};
+
+/// Template of the `.env` file. The API key is stored encrypted in the config
+/// file instead, see `trustblock config set api_key`.
+const ENV_TEMPLATE: &str = "# Plain text API key. Prefer `trustblock config set api_key <API_KEY>`, which stores it encrypted
+API_KEY=
+";
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Parser)]
@@ -11,18 +22,16 @@ pub struct InitArgs {
     #[clap(
         short,
         long,
-        help = "Trustblock API key to add to .env",
-        long_help = "Trustblock API key, which you can get in your profile. If supplied, it will be added automatically to .env"
+        help = "Trustblock API key to store encrypted in the config file",
+        long_help = "Trustblock API key, which you can get in your profile. If supplied, it is encrypted with a passphrase and stored in the selected profile of ~/.trustblock/config.toml"
     )]
     api_key: Option<String>,
 }
 
 impl Cmd for InitArgs {
-    fn run(self) -> eyre::Result<()> {
+    fn run(self) -> eyre::Result<CommandOutput> {
         let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
 
-        let api_key = self.api_key.unwrap_or_default();
-
         // Create the path to the .trustblock directory
         let cli_dir = home_dir.join(CLI_PATH);
 
@@ -35,18 +44,23 @@ impl Cmd for InitArgs {
         let env_path = cli_dir.join(".env");
 
         if env_path.exists() {
-            println!(".env file already exists at {env_path:?}");
-            return Ok(());
+            sh_println!(".env file already exists at {env_path:?}");
+        } else {
+            write_private(&env_path, ENV_TEMPLATE)?;
+
+            sh_println!("Created .env file at {env_path:?}");
         }
 
-        let mut env_file = File::create(&env_path)?;
+        if let Some(api_key) = self.api_key {
+            let (profile, _) = selected_profile(&ConfigFile::load()?);
 
-        let env_data = format!("API_KEY={api_key}");
+            let encrypted_api_key = vault::encrypt(&api_key, &vault::new_passphrase()?)?;
 
-        env_file.write_all(env_data.as_bytes())?;
+            let config_path = ConfigFile::set_value(Some(&profile), "api_key", &encrypted_api_key)?;
 
-        println!("Created .env file at {env_path:?}");
+            sh_println!("Stored encrypted API key in profile {profile} of {config_path:?}");
+        }
 
-        Ok(())
+        Ok(CommandOutput::new("init"))
     }
 }
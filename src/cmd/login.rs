@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use eyre::{eyre, ContextCompat};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    config::{ConfigFile, Settings},
+    error::TrustblockError,
+    sh_println,
+    shell::CommandOutput,
+    vault,
+};
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Device authorization started with `<auth_endpoint>device/code`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+const fn default_interval() -> u64 {
+    5
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct LoginArgs {
+    #[clap(long, help = "Prints the login URL without opening a browser")]
+    no_browser: bool,
+}
+
+impl LoginArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let settings = Settings::load()?;
+        let client = settings.http.client()?;
+
+        let device_code = request_device_code(&client, &settings).await?;
+
+        let login_url = device_code
+            .verification_uri_complete
+            .clone()
+            .unwrap_or_else(|| device_code.verification_uri.clone());
+
+        sh_println!(
+            "Open {login_url} and confirm the code {}\n",
+            device_code.user_code
+        );
+
+        if !self.no_browser && open_browser(&login_url) {
+            sh_println!("Opened the login page in your browser\n");
+        }
+
+        sh_println!("Waiting for approval...\n");
+
+        let token = poll_token(&client, &settings, &device_code).await?;
+
+        let encrypted_token = vault::encrypt(&token, &vault::new_passphrase()?)?;
+
+        let path = ConfigFile::set_value(Some(&settings.profile), "api_key", &encrypted_token)?;
+
+        sh_println!(
+            "Logged in. Stored encrypted API key in profile {} of {path:?}",
+            settings.profile
+        );
+
+        Ok(CommandOutput::new("login"))
+    }
+}
+
+/// Starts the device authorization flow.
+pub async fn request_device_code(client: &Client, settings: &Settings) -> eyre::Result<DeviceCode> {
+    // An abandoned device code expires on its own, so a retry is harmless
+    let response = settings
+        .retry
+        .send(true, || {
+            client
+                .post(format!("{}device/code", settings.auth_endpoint))
+                .json(&json!({ "clientId": "trustblock-cli" }))
+        })
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(
+            eyre::Report::new(TrustblockError::from_response(response).await)
+                .wrap_err("Could not start the login"),
+        );
+    }
+
+    Ok(response.json::<DeviceCode>().await?)
+}
+
+/// Polls `<auth_endpoint>device/token` until the login is approved, denied or
+/// expired, and returns the issued API key.
+pub async fn poll_token(
+    client: &Client,
+    settings: &Settings,
+    device_code: &DeviceCode,
+) -> eyre::Result<String> {
+    let expires_at = Instant::now() + Duration::from_secs(device_code.expires_in);
+    let mut interval = Duration::from_secs(device_code.interval);
+
+    loop {
+        if Instant::now() >= expires_at {
+            return Err(eyre!("Login code expired. Run `trustblock login` again"));
+        }
+
+        tokio::time::sleep(interval).await;
+
+        let response = settings
+            .retry
+            .send(true, || {
+                client
+                    .post(format!("{}device/token", settings.auth_endpoint))
+                    .json(&json!({
+                        "deviceCode": device_code.device_code,
+                        "grantType": DEVICE_CODE_GRANT_TYPE,
+                    }))
+            })
+            .await?;
+
+        if response.status().is_success() {
+            let body = response.json::<Value>().await?;
+
+            return body["accessToken"]
+                .as_str()
+                .map(ToString::to_string)
+                .wrap_err("Login response has no access token");
+        }
+
+        let status = response.status();
+        let body = response.json::<Value>().await.unwrap_or_default();
+
+        match body["error"].as_str() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += Duration::from_secs(5),
+            Some("expired_token") => {
+                return Err(eyre!("Login code expired. Run `trustblock login` again"))
+            }
+            Some("access_denied") => {
+                return Err(TrustblockError::Unauthorized {
+                    message: "Login was denied".to_string(),
+                }
+                .into())
+            }
+            _ => return Err(TrustblockError::from_parts(status, &body, None).into()),
+        }
+    }
+}
+
+/// Opens `url` in the default browser, returning whether it could be started.
+fn open_browser(url: &str) -> bool {
+    let command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(url).spawn()
+    } else if cfg!(windows) {
+        std::process::Command::new("cmd")
+            .args(["/C", "start", "", url])
+            .spawn()
+    } else {
+        std::process::Command::new("xdg-open").arg(url).spawn()
+    };
+
+    command.is_ok()
+}
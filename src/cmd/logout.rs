@@ -0,0 +1,64 @@
+use clap::Parser;
+use reqwest::Client;
+
+use crate::{
+    config::{ConfigFile, Settings, Source},
+    constants::TRUSTBLOCK_API_KEY_HEADER,
+    error::TrustblockError,
+    sh_println,
+    shell::CommandOutput,
+};
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct LogoutArgs {}
+
+impl LogoutArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let mut output = CommandOutput::new("logout");
+
+        let settings = Settings::load()?;
+
+        if !matches!(settings.api_key_source, Some(Source::Profile(_))) {
+            sh_println!("Not logged in with profile {}", settings.profile);
+            return Ok(output);
+        }
+
+        if let Err(error) = revoke(&settings.http.client()?, &settings).await {
+            output.warn(format!(
+                "Could not revoke the API key, it is only removed locally: {error:#}"
+            ));
+        }
+
+        let path = ConfigFile::unset_value(Some(&settings.profile), "api_key")?;
+
+        sh_println!(
+            "Logged out. Removed the API key from profile {} of {path:?}",
+            settings.profile
+        );
+
+        Ok(output)
+    }
+}
+
+/// Revokes the API key of the profile.
+pub async fn revoke(client: &Client, settings: &Settings) -> eyre::Result<()> {
+    let api_key = settings.api_key(None)?;
+
+    // Revoking a key twice has the same effect
+    let response = settings
+        .retry
+        .send(true, || {
+            client
+                .post(format!("{}revoke", settings.auth_endpoint))
+                .header(TRUSTBLOCK_API_KEY_HEADER, &api_key)
+        })
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(TrustblockError::from_response(response).await.into());
+    }
+
+    Ok(())
+}
@@ -1,7 +1,16 @@
 pub mod clean;
+pub mod config;
+pub mod doctor;
 pub mod init;
+pub mod login;
+pub mod logout;
 pub mod publish_audit;
+pub mod publish_batch;
+pub mod self_update;
 pub mod trustblock;
+pub mod validate;
+pub mod verify_report;
+pub mod whoami;
 
 mod utils;
 
@@ -1,16 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, ValueHint};
-use eyre::eyre;
-use itertools::Itertools;
-use reqwest::{Client, StatusCode};
-use serde_json::Value;
 
+pub use crate::client::ReportSource;
 use crate::{
-    cmd::utils::{generate_pdf_from_url, upload_ipfs},
-    constants::{AUDIT_ENDPOINT, TRUSTBLOCK_API_KEY_HEADER},
-    types::{Audit, Chains, Project},
-    utils::{apply_dotenv, parse_json, validate_links, validate_pdf},
+    client::{build_audit, TrustblockClient},
+    config::Settings,
+    ipfs::file_cid,
+    journal::PublishJournal,
+    sh_println,
+    shell::{is_json, CommandOutput},
+    types::Audit,
+    utils::{parse_json, validate_links, validate_pdf},
+    validation::ensure_valid_audit,
 };
 
 #[allow(clippy::module_name_repetitions)]
@@ -51,95 +53,113 @@ pub struct PublishAuditArgs {
 
     #[clap(short = 'k', long)]
     api_key: Option<String>,
+
+    #[clap(
+        long,
+        help = "Prints the audit payload without uploading or publishing anything",
+        long_help = "Runs every local step (parsing, chain derivation, issue transformation and computing the report CID) and prints the audit payload that would be published. Nothing is uploaded to IPFS and nothing is created on Trustblock."
+    )]
+    dry_run: bool,
+
+    #[clap(
+        long,
+        help = "Resumes a failed publish, skipping steps that already completed",
+        long_help = "Resumes a previous publish of the same audit file and report from the journal in ~/.trustblock/journal/. Steps that already completed, like the report upload, are skipped.",
+        conflicts_with = "dry_run"
+    )]
+    resume: bool,
 }
 
 impl PublishAuditArgs {
     #[allow(clippy::future_not_send)]
-    pub async fn run(self) -> eyre::Result<()> {
-        apply_dotenv()?;
-
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
         let audit_data = parse_json::<Audit>(&self.audit_file_path)?;
 
-        let api_key = match self.api_key {
-            Some(token) => token,
-            None => std::env::var("API_KEY")?,
-        };
-
-        let project_id = audit_data
-            .project
-            .clone()
-            .fetch_project_id(&api_key)
-            .await?;
-
-        let report_pdf_file_path = match self.report_pdf_file_path {
-            Some(path) => path,
-            None => {
-                generate_pdf_from_url(self.report_url.expect("should not fail"), &api_key).await?
-            }
-        };
-
-        let (report_hash, report_file_url) = upload_ipfs(report_pdf_file_path, &api_key).await?;
+        let settings = Settings::load()?;
 
-        let client = Client::new();
+        ensure_valid_audit(&audit_data, settings.max_issues_per_severity)?;
 
-        let audit_endpoint =
-            std::env::var("AUDIT_ENDPOINT").unwrap_or_else(|_| AUDIT_ENDPOINT.to_string());
+        if self.dry_run {
+            return Self::dry_run(&settings, audit_data, self.report_pdf_file_path.as_deref());
+        }
 
-        let chains = audit_data
-            .contracts
-            .iter()
-            .map(|contract| contract.chain)
-            .unique()
-            .collect::<Vec<Chains>>();
+        let api_key = settings.api_key(self.api_key)?;
+        let client = TrustblockClient::new(settings, api_key)?;
 
-        let project = Project {
-            id: project_id,
-            ..audit_data.clone().project
+        let report = match self.report_pdf_file_path {
+            Some(path) => ReportSource::Pdf(path),
+            None => ReportSource::Url(self.report_url.expect("should not fail")),
         };
 
-        let audit_data_send = Audit {
-            chains,
-            report_hash: report_hash.clone(),
-            report_file_url,
-            project,
-            ..audit_data
-        };
+        let mut journal = PublishJournal::open(&self.audit_file_path, &report, self.resume)?;
 
-        let response = client
-            .post(audit_endpoint)
-            .header(TRUSTBLOCK_API_KEY_HEADER, api_key)
-            .json(&audit_data_send)
-            .send()
-            .await?;
+        let output = publish(&client, audit_data, report, &mut journal).await?;
 
-        let status = response.status();
+        sh_println!("Audit published successfully!\n");
 
-        let body = response.json::<Value>().await?;
+        Ok(output)
+    }
 
-        //TODO: create a separate error struct with "thiserror" crate
-        match status {
-            StatusCode::BAD_REQUEST => {
-                let error = &body["error"];
+    fn dry_run(
+        settings: &Settings,
+        audit_data: Audit,
+        report_pdf_file_path: Option<&Path>,
+    ) -> eyre::Result<CommandOutput> {
+        let mut output = CommandOutput::new("publish-audit");
 
-                if error == "Report hash is not a unique value." {
-                    println!("Audit already published to DB!\n");
-                    return Ok(());
-                }
+        let (report_hash, report_file_url) = match report_pdf_file_path {
+            Some(path) => {
+                let cid = file_cid(path)?;
+                let report_url = settings.store.store()?.report_url(&cid);
 
-                if error == "Project domain is not a unique value." {
-                    println!("Project already exists on DB!\n");
-                    return Ok(());
-                }
+                output.report_cid = Some(cid.clone());
+                output.report_url = Some(report_url.clone());
 
-                Err(eyre!(
-                    "Could not publish to DB. Check validity of the audit data: {body} "
-                ))
+                (cid, report_url)
             }
-            StatusCode::CREATED => {
-                println!("Audit published successfully!\n");
-                Ok(())
+            None => {
+                output.warn("Web based report is not rendered in a dry run");
+                (String::new(), String::new())
             }
-            _ => Err(eyre!("Could not publish to DB. Response: {status}")),
+        };
+
+        let audit_data_send = build_audit(audit_data, None, report_hash, report_file_url);
+
+        sh_println!("Dry run: nothing was uploaded or published\n");
+
+        if is_json() {
+            output.audit = Some(serde_json::to_value(&audit_data_send)?);
+        } else {
+            sh_println!("{}", serde_json::to_string_pretty(&audit_data_send)?);
         }
+
+        Ok(output)
     }
 }
+
+/// Publishes validated audit data, recording each completed step in the
+/// journal, and reports the result as a command output.
+#[allow(clippy::future_not_send)]
+pub async fn publish(
+    client: &TrustblockClient,
+    audit_data: Audit,
+    report: ReportSource,
+    journal: &mut PublishJournal,
+) -> eyre::Result<CommandOutput> {
+    let published = client
+        .publish_audit_with_journal(audit_data, report, journal)
+        .await?;
+
+    let mut output = CommandOutput::new("publish-audit");
+
+    for warning in published.warnings {
+        output.warn(warning);
+    }
+
+    output.audit_id = published.audit_id;
+    output.project_id = published.project_id;
+    output.report_cid = Some(published.report_cid);
+    output.report_url = Some(published.report_url);
+
+    Ok(output)
+}
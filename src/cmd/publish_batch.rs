@@ -0,0 +1,258 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueHint};
+use eyre::eyre;
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+
+use crate::{
+    client::{ReportSource, TrustblockClient},
+    cmd::publish_audit::publish,
+    error::ExitCode,
+    journal::PublishJournal,
+    sh_println,
+    shell::{CommandOutput, ResultStatus},
+    types::Audit,
+    utils::{parse_json, validate_links, validate_pdf},
+    validation::ensure_valid_audit,
+};
+
+/// One audit to publish. Relative paths are resolved against the manifest
+/// directory.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestEntry {
+    pub audit: PathBuf,
+    pub pdf: Option<PathBuf>,
+    pub url: Option<String>,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct PublishBatchArgs {
+    #[clap(
+        short,
+        long,
+        help = "File path to YAML, JSON or CSV manifest of audits to publish",
+        long_help = "File path to a YAML, JSON or CSV manifest. Each entry has an `audit` JSON file and either a `pdf` report file or a report `url`. CSV manifests use an `audit,pdf,url` header.",
+        value_name = "MANIFEST_FILE",
+        value_hint = ValueHint::FilePath,
+        required(true)
+    )]
+    manifest: PathBuf,
+
+    #[clap(
+        short = 'j',
+        long,
+        help = "Maximum number of audits published at the same time",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    concurrency: u16,
+
+    #[clap(short = 'k', long)]
+    api_key: Option<String>,
+
+    #[clap(
+        long,
+        help = "Resumes failed publishes, skipping steps that already completed"
+    )]
+    resume: bool,
+}
+
+impl PublishBatchArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let entries = parse_manifest(&self.manifest)?;
+
+        let client = TrustblockClient::from_env(self.api_key)?;
+
+        sh_println!("Publishing {} audits\n", entries.len());
+
+        let mut results = stream::iter(entries.into_iter().enumerate())
+            .map(|(index, entry)| {
+                let client = &client;
+
+                async move {
+                    let audit_file = entry.audit.to_string_lossy().to_string();
+
+                    let output = match publish_entry(client, entry, self.resume).await {
+                        Ok(output) => output,
+                        Err(error) => CommandOutput::error("publish-audit", &error),
+                    };
+
+                    (
+                        index,
+                        CommandOutput {
+                            audit_file: Some(audit_file),
+                            ..output
+                        },
+                    )
+                }
+            })
+            .buffer_unordered(usize::from(self.concurrency))
+            .collect::<Vec<(usize, CommandOutput)>>()
+            .await;
+
+        results.sort_by_key(|(index, _)| *index);
+
+        let results = results
+            .into_iter()
+            .map(|(_, output)| output)
+            .collect::<Vec<CommandOutput>>();
+
+        print_summary(&results);
+
+        let failed = results
+            .iter()
+            .filter(|output| outcome(output) == Outcome::Failed)
+            .count();
+
+        let mut output = CommandOutput {
+            results,
+            ..CommandOutput::new("publish-batch")
+        };
+
+        if failed > 0 {
+            output.status = ResultStatus::Error;
+            output.error = Some(format!(
+                "{failed} of {} audits failed to publish",
+                output.results.len()
+            ));
+            output.exit_code = Some(ExitCode::Failure as i32);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Reads the manifest, picking the format from the file extension.
+pub fn parse_manifest(path: &Path) -> eyre::Result<Vec<ManifestEntry>> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let entries: Vec<ManifestEntry> = match extension.as_str() {
+        "yaml" | "yml" => serde_yaml::from_reader(File::open(path)?)?,
+        "json" => parse_json(&path.to_path_buf())?,
+        "csv" => csv::Reader::from_path(path)?
+            .deserialize()
+            .collect::<Result<_, _>>()?,
+        _ => {
+            return Err(eyre!(
+                "Unsupported manifest format {path:?}. Use a .yaml, .yml, .json or .csv file"
+            ))
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ManifestEntry {
+            audit: base_dir.join(entry.audit),
+            pdf: entry.pdf.map(|pdf| base_dir.join(pdf)),
+            ..entry
+        })
+        .collect())
+}
+
+#[allow(clippy::future_not_send)]
+async fn publish_entry(
+    client: &TrustblockClient,
+    entry: ManifestEntry,
+    resume: bool,
+) -> eyre::Result<CommandOutput> {
+    let audit_data = parse_json::<Audit>(&entry.audit)?;
+
+    ensure_valid_audit(&audit_data, client.settings().max_issues_per_severity)?;
+
+    let report = match (entry.pdf, entry.url) {
+        (Some(pdf), None) => ReportSource::Pdf(validate_pdf(&pdf.to_string_lossy())?),
+        (None, Some(url)) => ReportSource::Url(validate_links(&url)?),
+        _ => {
+            return Err(eyre!(
+                "Manifest entry for {:?} needs exactly one of `pdf` or `url`",
+                entry.audit
+            ))
+        }
+    };
+
+    let mut journal = PublishJournal::open(&entry.audit, &report, resume)?;
+
+    publish(client, audit_data, report, &mut journal).await
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Published,
+    Duplicate,
+    Failed,
+}
+
+fn outcome(output: &CommandOutput) -> Outcome {
+    match (output.status, output.exit_code) {
+        (ResultStatus::Success, _) => Outcome::Published,
+        (ResultStatus::Error, Some(code)) if code == ExitCode::Duplicate as i32 => {
+            Outcome::Duplicate
+        }
+        (ResultStatus::Error, _) => Outcome::Failed,
+    }
+}
+
+fn print_summary(results: &[CommandOutput]) {
+    let rows = results
+        .iter()
+        .map(|output| {
+            let (status, detail) = match outcome(output) {
+                Outcome::Published => ("published", output.report_cid.clone()),
+                Outcome::Duplicate => ("duplicate", output.error.clone()),
+                Outcome::Failed => ("error", output.error.clone()),
+            };
+
+            let detail = detail
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            (
+                output.audit_file.clone().unwrap_or_default(),
+                status,
+                detail,
+            )
+        })
+        .collect::<Vec<(String, &str, String)>>();
+
+    let audit_width = rows
+        .iter()
+        .map(|(audit, _, _)| audit.len())
+        .chain([5])
+        .max()
+        .unwrap_or_default();
+
+    sh_println!("{:audit_width$}  {:9}  DETAIL", "AUDIT", "STATUS");
+
+    for (audit, status, detail) in &rows {
+        sh_println!("{audit:audit_width$}  {status:9}  {detail}");
+    }
+
+    let count = |expected| {
+        results
+            .iter()
+            .filter(|output| outcome(output) == expected)
+            .count()
+    };
+
+    sh_println!(
+        "\nPublished: {}, duplicates: {}, errors: {}\n",
+        count(Outcome::Published),
+        count(Outcome::Duplicate),
+        count(Outcome::Failed)
+    );
+}
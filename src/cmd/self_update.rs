@@ -0,0 +1,74 @@
+use clap::Parser;
+use semver::Version;
+
+use crate::{
+    config::Settings,
+    sh_println,
+    shell::CommandOutput,
+    update::{extract_binary, replace_binary, ReleaseSource, CURRENT_VERSION, TARGET},
+};
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct SelfUpdateArgs {
+    #[arg(
+        long,
+        value_name = "VERSION",
+        help = "Installs this version instead of the latest one"
+    )]
+    version: Option<Version>,
+
+    #[arg(
+        long,
+        help = "Reinstalls the release even if it is the current version"
+    )]
+    force: bool,
+}
+
+impl SelfUpdateArgs {
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let current_version = Version::parse(CURRENT_VERSION)?;
+
+        let source = ReleaseSource::new(&Settings::load()?)?;
+        let release = source.find(self.version.as_ref()).await?;
+
+        let output = CommandOutput {
+            version: Some(release.version.to_string()),
+            ..CommandOutput::new("self-update")
+        };
+
+        if !self.force {
+            if release.version == current_version {
+                sh_println!("Trustblock CLI {current_version} is up to date");
+                return Ok(output);
+            }
+
+            if self.version.is_none() && release.version < current_version {
+                sh_println!(
+                    "Trustblock CLI {current_version} is newer than the latest release {}",
+                    release.version
+                );
+                return Ok(output);
+            }
+        }
+
+        sh_println!(
+            "Downloading Trustblock CLI {} for {TARGET}\n",
+            release.version
+        );
+
+        let archive = source.download(&release).await?;
+        let binary = extract_binary(&archive)?;
+
+        let path = std::env::current_exe()?;
+
+        replace_binary(&path, &binary, &release.version)?;
+
+        sh_println!(
+            "Updated Trustblock CLI from {current_version} to {} at {path:?}",
+            release.version
+        );
+
+        Ok(output)
+    }
+}
@@ -1,16 +1,113 @@
-use std::str;
+use std::{path::PathBuf, str};
 
-use clap::{command, Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueHint};
 
-use crate::cmd::{clean::CleanArgs, init::InitArgs, publish_audit::PublishAuditArgs};
+use crate::{
+    cmd::{
+        clean::CleanArgs, config::ConfigArgs, doctor::DoctorArgs, init::InitArgs, login::LoginArgs,
+        logout::LogoutArgs, publish_audit::PublishAuditArgs, publish_batch::PublishBatchArgs,
+        self_update::SelfUpdateArgs, validate::ValidateArgs, verify_report::VerifyReportArgs,
+        whoami::WhoamiArgs,
+    },
+    config,
+    error::EXIT_CODES_HELP,
+    shell::OutputFormat,
+};
 
 #[derive(Debug, Parser)]
-#[command(about = "Trustblock CLI", version)]
+#[command(about = "Trustblock CLI", version, after_help = EXIT_CODES_HELP)]
 pub struct Cli {
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "Format of the command result printed on stdout"
+    )]
+    pub output: OutputFormat,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Profile of ~/.trustblock/config.toml to use",
+        long_help = "Profile of ~/.trustblock/config.toml to use. Built-in profiles are `production`, `staging` and `local`. Defaults to `TRUSTBLOCK_PROFILE`, then `default_profile` of the config file, then `production`"
+    )]
+    pub profile: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help_heading = "Network",
+        help = "Maximum time to connect to a server [default: 10]"
+    )]
+    pub connect_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help_heading = "Network",
+        help = "Maximum time of an API request, excluding uploads and downloads [default: 120]"
+    )]
+    pub timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        help_heading = "Network",
+        help = "Proxy every request goes through. Defaults to HTTPS_PROXY, HTTP_PROXY or ALL_PROXY"
+    )]
+    pub proxy: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PEM_FILE",
+        value_hint = ValueHint::FilePath,
+        help_heading = "Network",
+        help = "PEM file of root certificates to trust in addition to the system ones"
+    )]
+    pub ca_bundle: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help_heading = "Network",
+        help = "Accepts invalid TLS certificates. Only use it for local testing"
+    )]
+    pub insecure: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Overrides the settings of the profile with the global flags, see
+    /// [`config::set_profile`] and [`config::set_flag`].
+    pub fn apply_global_flags(&self) {
+        config::set_profile(self.profile.clone());
+
+        config::set_flag(
+            "connect_timeout_secs",
+            self.connect_timeout.map(|seconds| seconds.to_string()),
+        );
+        config::set_flag(
+            "timeout_secs",
+            self.timeout.map(|seconds| seconds.to_string()),
+        );
+        config::set_flag("proxy_url", self.proxy.clone());
+        config::set_flag(
+            "ca_bundle",
+            self.ca_bundle
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+        );
+        config::set_flag("insecure", self.insecure.then(|| true.to_string()));
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     #[command(
@@ -20,9 +117,70 @@ pub enum Commands {
     )]
     PublishAudit(PublishAuditArgs),
 
+    #[command(
+        about = "Publishes many audits listed in a manifest file",
+        arg_required_else_help = true,
+        next_line_help = true
+    )]
+    PublishBatch(PublishBatchArgs),
+
+    #[command(
+        about = "Validates audit data without publishing it",
+        arg_required_else_help = true
+    )]
+    Validate(ValidateArgs),
+
+    #[command(
+        about = "Verifies a report PDF against a published report hash",
+        arg_required_else_help = true
+    )]
+    VerifyReport(VerifyReportArgs),
+
+    #[command(
+        about = "Gets, sets, lists and unsets settings of the selected profile",
+        arg_required_else_help = true
+    )]
+    Config(ConfigArgs),
+
+    #[command(about = "Logs in to Trustblock in the browser and stores the API key")]
+    Login(LoginArgs),
+
+    #[command(about = "Revokes the stored API key and removes it")]
+    Logout(LogoutArgs),
+
+    #[command(about = "Shows the auditor the API key belongs to")]
+    Whoami(WhoamiArgs),
+
+    #[command(about = "Diagnoses the configuration, API key and endpoints")]
+    Doctor(DoctorArgs),
+
+    #[command(about = "Installs the latest release, or the given version, in place")]
+    SelfUpdate(SelfUpdateArgs),
+
     #[command(about = "Initializes .trustblock folder")]
     Init(InitArgs),
 
     #[command(about = "Cleans .trustblock folder")]
     Clean(CleanArgs),
 }
+
+impl Commands {
+    /// Subcommand name as typed on the command line.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::PublishAudit(_) => "publish-audit",
+            Self::PublishBatch(_) => "publish-batch",
+            Self::Validate(_) => "validate",
+            Self::VerifyReport(_) => "verify-report",
+            Self::Config(_) => "config",
+            Self::Login(_) => "login",
+            Self::Logout(_) => "logout",
+            Self::Whoami(_) => "whoami",
+            Self::Doctor(_) => "doctor",
+            Self::SelfUpdate(_) => "self-update",
+            Self::Init(_) => "init",
+            Self::Clean(_) => "clean",
+        }
+    }
+}
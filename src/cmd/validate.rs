@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueHint};
+
+use crate::{
+    cmd::utils::Cmd, config::Settings, sh_println, shell::CommandOutput, types::Audit,
+    utils::parse_json, validation::ensure_valid_audit,
+};
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct ValidateArgs {
+    #[clap(
+        short,
+        long = "audit-data",
+        help = "File path to JSON file with Audit data",
+        value_name = "AUDIT_DATA_JSON_FILE",
+        value_hint = ValueHint::FilePath,
+        required(true)
+    )]
+    audit_file_path: PathBuf,
+}
+
+impl Cmd for ValidateArgs {
+    fn run(self) -> eyre::Result<CommandOutput> {
+        let audit_data = parse_json::<Audit>(&self.audit_file_path)?;
+
+        ensure_valid_audit(&audit_data, Settings::load()?.max_issues_per_severity)?;
+
+        sh_println!("Audit data is valid");
+
+        Ok(CommandOutput::new("validate"))
+    }
+}
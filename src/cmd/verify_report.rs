@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueHint};
+use eyre::eyre;
+use reqwest::Client;
+use serde_json::Value;
+use tempfile::NamedTempFile;
+
+use crate::{
+    config::Settings,
+    constants::TRUSTBLOCK_API_KEY_HEADER,
+    error::{ExitCode, TrustblockError},
+    ipfs::{file_cid, same_content},
+    sh_println,
+    shell::{CommandOutput, ResultStatus},
+    utils::validate_pdf,
+};
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct VerifyReportArgs {
+    #[clap(
+        short,
+        long,
+        help = "File path to the audit report PDF file to verify",
+        value_name = "AUDIT_REPORT_PDF_FILE",
+        value_hint = ValueHint::FilePath,
+        value_parser = validate_pdf,
+        required(true)
+    )]
+    pdf: PathBuf,
+
+    #[clap(
+        short,
+        long,
+        help = "Published report hash (IPFS CID) to compare against",
+        value_name = "REPORT_HASH",
+        required_unless_present = "audit_id",
+        conflicts_with = "audit_id"
+    )]
+    cid: Option<String>,
+
+    #[clap(
+        long,
+        help = "Id of the published audit whose report hash is compared against",
+        value_name = "AUDIT_ID"
+    )]
+    audit_id: Option<String>,
+
+    #[clap(
+        short,
+        long,
+        help = "Also downloads the published report from the IPFS gateway and verifies it"
+    )]
+    fetch: bool,
+
+    #[clap(short = 'k', long)]
+    api_key: Option<String>,
+}
+
+impl VerifyReportArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let settings = Settings::load()?;
+        let client = settings.http.client()?;
+
+        let local_cid = file_cid(&self.pdf)?;
+
+        let mut output = CommandOutput {
+            audit_id: self.audit_id.clone(),
+            ..CommandOutput::new("verify-report")
+        };
+
+        let published_cid = match (self.cid, &self.audit_id) {
+            (Some(cid), _) => cid,
+            (None, Some(audit_id)) => {
+                if self.api_key.is_none() {
+                    for warning in settings.warnings() {
+                        output.warn(warning);
+                    }
+                }
+
+                fetch_report_hash(&client, &settings, audit_id, self.api_key.as_deref()).await?
+            }
+            (None, None) => return Err(eyre!("Either --cid or --audit-id is required")),
+        };
+
+        // Reports are fetched from the gateway of the report store of the profile
+        let report_url = settings.store.store()?.report_url(&published_cid);
+
+        sh_println!("Local report CID:     {local_cid}");
+        sh_println!("Published report CID: {published_cid}");
+
+        let mut matches = same_content(&local_cid, &published_cid)?;
+
+        if self.fetch {
+            let gateway_cid = fetch_gateway_cid(&settings, &report_url).await?;
+
+            sh_println!("Gateway report CID:   {gateway_cid}");
+
+            if !same_content(&gateway_cid, &published_cid)? {
+                output.warn(format!(
+                    "Report served by the gateway does not hash to {published_cid}"
+                ));
+            }
+
+            matches &= same_content(&gateway_cid, &local_cid)?;
+        }
+
+        output.report_cid = Some(published_cid);
+        output.report_url = Some(report_url);
+
+        if matches {
+            sh_println!("\nReport matches the published audit report\n");
+        } else {
+            sh_println!("\nReport does not match the published audit report\n");
+
+            output.status = ResultStatus::Error;
+            output.error = Some("Report does not match the published audit report".to_string());
+            output.exit_code = Some(ExitCode::Mismatch as i32);
+        }
+
+        Ok(output)
+    }
+}
+
+async fn fetch_report_hash(
+    client: &Client,
+    settings: &Settings,
+    audit_id: &str,
+    api_key: Option<&str>,
+) -> eyre::Result<String> {
+    // The audit can be fetched without an API key
+    let api_key = if api_key.is_some() || settings.api_key.is_some() {
+        Some(settings.api_key(api_key.map(ToString::to_string))?)
+    } else {
+        None
+    };
+
+    let response = settings
+        .retry
+        .send(true, || {
+            let request = client.get(format!("{}{audit_id}", settings.audit_endpoint));
+
+            match &api_key {
+                Some(api_key) => request.header(TRUSTBLOCK_API_KEY_HEADER, api_key),
+                None => request,
+            }
+        })
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(
+            eyre::Report::new(TrustblockError::from_response(response).await)
+                .wrap_err("Could not fetch the audit"),
+        );
+    }
+
+    let audit = response.json::<Value>().await?;
+
+    audit["reportHash"]
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| eyre!("Audit {audit_id} has no report hash"))
+}
+
+async fn fetch_gateway_cid(settings: &Settings, report_url: &str) -> eyre::Result<String> {
+    let client = settings.http.transfer_client()?;
+
+    let response = settings
+        .retry
+        .send(true, || client.get(report_url))
+        .await?
+        .error_for_status()?;
+
+    let report_file = NamedTempFile::new()?;
+
+    std::fs::write(report_file.path(), response.bytes().await?)?;
+
+    file_cid(report_file.path())
+}
@@ -0,0 +1,51 @@
+use clap::Parser;
+use serde_json::Value;
+
+use crate::{client::TrustblockClient, sh_println, shell::CommandOutput};
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Parser)]
+pub struct WhoamiArgs {
+    #[clap(short = 'k', long)]
+    api_key: Option<String>,
+}
+
+impl WhoamiArgs {
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self) -> eyre::Result<CommandOutput> {
+        let client = TrustblockClient::from_env(self.api_key)?;
+
+        let identity = client.get_identity().await?;
+
+        sh_println!(
+            "Authenticated as {} with profile {}",
+            describe_identity(&identity),
+            client.settings().profile
+        );
+
+        let mut output = CommandOutput {
+            identity: Some(identity),
+            ..CommandOutput::new("whoami")
+        };
+
+        for warning in client.settings().warnings() {
+            output.warn(warning);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Formats an identity as `name <email>`, with the fields that are present.
+#[must_use]
+pub fn describe_identity(identity: &Value) -> String {
+    match (identity["name"].as_str(), identity["email"].as_str()) {
+        (Some(name), Some(email)) => format!("{name} <{email}>"),
+        (Some(name), None) => name.to_string(),
+        (None, Some(email)) => email.to_string(),
+        (None, None) => identity["id"]
+            .as_str()
+            .unwrap_or("unknown auditor")
+            .to_string(),
+    }
+}
@@ -0,0 +1,709 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env::VarError,
+    fmt,
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use eyre::{eyre, ContextCompat, WrapErr};
+use serde::{Deserialize, Deserializer, Serialize};
+use toml_edit::{Document, Item, Table};
+
+use crate::{
+    constants::{
+        CLI_PATH, GITHUB_RELEASES, KUBO_API_URL, LOCAL_API_URL, PDF_GENERATE_ENDPOINT,
+        PRODUCTION_API_URL, STAGING_API_URL, WEB3_STORAGE_API_URL,
+    },
+    http::HttpSettings,
+    retry::RetryPolicy,
+    store::StoreSettings,
+    utils::apply_dotenv,
+    validation::DEFAULT_MAX_ISSUES_PER_SEVERITY,
+    vault::{self, write_private},
+};
+
+/// Profile used when none is selected.
+pub const DEFAULT_PROFILE: &str = "production";
+
+/// Type of the value of a setting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    /// Masked when shown and encrypted by `config set`.
+    Secret,
+    /// Whole number.
+    Number,
+    /// `true` or `false`.
+    Boolean,
+}
+
+/// A setting a profile can hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub name: &'static str,
+    /// Environment variable overriding the setting.
+    pub env_var: Option<&'static str>,
+    pub kind: Kind,
+}
+
+/// Declares [`Profile`], [`Profile::get`] and [`KEYS`] from a single list, so
+/// a setting cannot be missing from one of them.
+macro_rules! keys {
+    ($($name:ident: $env_var:expr, $kind:ident;)*) => {
+        /// Endpoints and API key of a Trustblock environment.
+        ///
+        /// Endpoints that are not set are derived from `api_url`.
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub struct Profile {
+            $(
+                #[serde(
+                    default,
+                    deserialize_with = "scalar",
+                    skip_serializing_if = "Option::is_none"
+                )]
+                pub $name: Option<String>,
+            )*
+        }
+
+        impl Profile {
+            /// Returns the value of one of [`KEYS`].
+            #[must_use]
+            pub fn get(&self, key: &str) -> Option<&String> {
+                match key {
+                    $(stringify!($name) => self.$name.as_ref(),)*
+                    _ => None,
+                }
+            }
+        }
+
+        /// Settings a profile can hold, with the environment variable
+        /// overriding each of them.
+        pub const KEYS: &[Key] = &[$(
+            Key {
+                name: stringify!($name),
+                env_var: $env_var,
+                kind: Kind::$kind,
+            },
+        )*];
+    };
+}
+
+keys! {
+    api_key: Some("API_KEY"), Secret;
+    api_url: None, Text;
+    audit_endpoint: Some("AUDIT_ENDPOINT"), Text;
+    audit_report_hash_endpoint: Some("AUDIT_REPORT_HASH_ENDPOINT"), Text;
+    project_slug_endpoint: Some("PROJECT_SLUG_ENDPOINT"), Text;
+    web3_storage_api_endpoint: Some("WEB3_STORAGE_API_ENDPOINT"), Text;
+    pdf_generate_endpoint: Some("PDF_GENERATE_ENDPOINT"), Text;
+    auth_endpoint: Some("AUTH_ENDPOINT"), Text;
+    max_retries: Some("MAX_RETRIES"), Number;
+    retry_backoff_ms: Some("RETRY_BACKOFF_MS"), Number;
+    retry_max_backoff_ms: Some("RETRY_MAX_BACKOFF_MS"), Number;
+    retry_jitter: Some("RETRY_JITTER"), Boolean;
+    audit_idempotency_key: Some("AUDIT_IDEMPOTENCY_KEY"), Boolean;
+    connect_timeout_secs: Some("CONNECT_TIMEOUT_SECS"), Number;
+    timeout_secs: Some("TIMEOUT_SECS"), Number;
+    proxy_url: Some("PROXY_URL"), Text;
+    ca_bundle: Some("CA_BUNDLE"), Text;
+    insecure: Some("TRUSTBLOCK_INSECURE"), Boolean;
+    update_check: None, Boolean;
+    release_url: Some("TRUSTBLOCK_RELEASE_URL"), Text;
+    max_issues_per_severity: Some("MAX_ISSUES_PER_SEVERITY"), Number;
+    report_store: Some("REPORT_STORE"), Text;
+    web3_storage_url: Some("WEB3_STORAGE_URL"), Text;
+    kubo_api_url: Some("KUBO_API_URL"), Text;
+    kubo_api_auth: Some("KUBO_API_AUTH"), Secret;
+    ipfs_gateway_url: Some("IPFS_GATEWAY_URL"), Text;
+    s3_endpoint: Some("S3_ENDPOINT"), Text;
+    s3_bucket: Some("S3_BUCKET"), Text;
+    s3_region: Some("S3_REGION"), Text;
+    s3_access_key_id: Some("S3_ACCESS_KEY_ID"), Text;
+    s3_secret_access_key: Some("S3_SECRET_ACCESS_KEY"), Secret;
+    s3_prefix: Some("S3_PREFIX"), Text;
+    s3_public_url: Some("S3_PUBLIC_URL"), Text;
+}
+
+/// Returns the entry of [`KEYS`] named `name`.
+#[must_use]
+pub fn key(name: &str) -> Option<&'static Key> {
+    KEYS.iter().find(|key| key.name == name)
+}
+
+/// Endpoints derived from `api_url` when they are not set.
+const API_PATHS: [(&str, &str); 5] = [
+    ("audit_endpoint", "audit/"),
+    ("audit_report_hash_endpoint", "audit/report-hash/"),
+    ("project_slug_endpoint", "project/"),
+    ("web3_storage_api_endpoint", "file/"),
+    ("auth_endpoint", "auth/"),
+];
+
+static PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+static FLAGS: Mutex<BTreeMap<&str, String>> = Mutex::new(BTreeMap::new());
+
+/// Selects the profile given with `--profile`.
+pub fn set_profile(profile: Option<String>) {
+    *PROFILE.lock().expect("profile lock poisoned") = profile;
+}
+
+/// Overrides one of [`KEYS`] with the value of a command line flag.
+pub fn set_flag(key: &'static str, value: Option<String>) {
+    let mut flags = FLAGS.lock().expect("flags lock poisoned");
+
+    match value {
+        Some(value) => flags.insert(key, value),
+        None => flags.remove(key),
+    };
+}
+
+/// Where the value of a setting comes from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Source {
+    Flag,
+    Env(&'static str),
+    DotEnv(&'static str),
+    DefaultProfile,
+    Profile(String),
+    #[default]
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag => write!(f, "flag"),
+            Self::Env(var) => write!(f, "env var {var}"),
+            Self::DotEnv(var) => write!(f, "{var} in ~/{CLI_PATH}/.env"),
+            Self::DefaultProfile => write!(f, "default_profile in config.toml"),
+            Self::Profile(profile) => write!(f, "profile {profile}"),
+            Self::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Reads a string, integer or boolean TOML value as a string, so numbers and
+/// booleans do not have to be quoted in the config file.
+fn scalar<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Scalar {
+        String(String),
+        Integer(i64),
+        Boolean(bool),
+    }
+
+    Ok(
+        Option::<Scalar>::deserialize(deserializer)?.map(|value| match value {
+            Scalar::String(value) => value,
+            Scalar::Integer(value) => value.to_string(),
+            Scalar::Boolean(value) => value.to_string(),
+        }),
+    )
+}
+
+impl Profile {
+    /// Profiles available without a config file.
+    #[must_use]
+    pub fn built_in(name: &str) -> Option<Self> {
+        let api_url = match name {
+            "production" => PRODUCTION_API_URL,
+            "staging" => STAGING_API_URL,
+            "local" => LOCAL_API_URL,
+            _ => return None,
+        };
+
+        Some(Self {
+            api_url: Some(api_url.to_string()),
+            ..Self::default()
+        })
+    }
+}
+
+/// Content of `~/.trustblock/config.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl ConfigFile {
+    /// Path of the config file, `TRUSTBLOCK_CONFIG` if set.
+    pub fn path() -> eyre::Result<PathBuf> {
+        if let Ok(path) = std::env::var("TRUSTBLOCK_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
+
+        Ok(home_dir.join(CLI_PATH).join("config.toml"))
+    }
+
+    /// Reads the config file, which is optional.
+    pub fn load() -> eyre::Result<Self> {
+        let path = Self::path()?;
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|error| eyre::Report::new(error).wrap_err(format!("Invalid config {path:?}")))
+    }
+
+    /// Sets a key of a profile, or the top level `default_profile` key when
+    /// `profile` is `None`, keeping the rest of the file as is.
+    pub fn set_value(profile: Option<&str>, key: &str, value: &str) -> eyre::Result<PathBuf> {
+        Self::edit(|document| {
+            let item = match profile {
+                Some(profile) => &mut profile_table(document, profile)?[key],
+                None => &mut document[key],
+            };
+
+            // Numbers and booleans are written unquoted, like they are typed
+            // by hand
+            let kind = self::key(key).map(|key| key.kind);
+            let mut new_item = if kind == Some(Kind::Number) {
+                value
+                    .parse::<i64>()
+                    .map_or_else(|_| toml_edit::value(value), toml_edit::value)
+            } else if kind == Some(Kind::Boolean) {
+                value
+                    .parse::<bool>()
+                    .map_or_else(|_| toml_edit::value(value), toml_edit::value)
+            } else {
+                toml_edit::value(value)
+            };
+
+            // Keeps the comments around a replaced value
+            if let (Some(old), Some(new)) = (item.as_value(), new_item.as_value_mut()) {
+                *new.decor_mut() = old.decor().clone();
+            }
+
+            *item = new_item;
+
+            Ok(())
+        })
+    }
+
+    /// Removes a key set with [`ConfigFile::set_value`].
+    pub fn unset_value(profile: Option<&str>, key: &str) -> eyre::Result<PathBuf> {
+        Self::edit(|document| {
+            match profile {
+                Some(profile) => profile_table(document, profile)?.remove(key),
+                None => document.remove(key),
+            };
+
+            Ok(())
+        })
+    }
+
+    fn edit(update: impl FnOnce(&mut Document) -> eyre::Result<()>) -> eyre::Result<PathBuf> {
+        let path = Self::path()?;
+
+        let mut document = if path.exists() {
+            std::fs::read_to_string(&path)?
+                .parse::<Document>()
+                .map_err(|error| {
+                    eyre::Report::new(error).wrap_err(format!("Invalid config {path:?}"))
+                })?
+        } else {
+            Document::new()
+        };
+
+        update(&mut document).wrap_err_with(|| format!("Invalid config {path:?}"))?;
+
+        if let Some(config_dir) = path.parent() {
+            std::fs::create_dir_all(config_dir)?;
+        }
+
+        write_private(&path, &document.to_string())?;
+
+        Ok(path)
+    }
+}
+
+/// Returns the `[profiles.<profile>]` table, creating it if needed.
+fn profile_table<'a>(document: &'a mut Document, profile: &str) -> eyre::Result<&'a mut Table> {
+    let profiles = document["profiles"].or_insert({
+        let mut profiles = Table::new();
+        profiles.set_implicit(true);
+        Item::Table(profiles)
+    });
+    let type_name = profiles.type_name();
+
+    let profile_item = profiles
+        .as_table_mut()
+        .wrap_err_with(|| format!("profiles should be a table, found {type_name}"))?
+        .entry(profile)
+        .or_insert(Item::Table(Table::new()));
+    let type_name = profile_item.type_name();
+
+    profile_item
+        .as_table_mut()
+        .wrap_err_with(|| format!("Profile {profile:?} should be a table, found {type_name}"))
+}
+
+/// Returns the profile selected with `--profile`, `TRUSTBLOCK_PROFILE` or
+/// `default_profile`, in that order, and where the selection comes from.
+#[must_use]
+pub fn selected_profile(config: &ConfigFile) -> (String, Source) {
+    let flag = PROFILE.lock().expect("profile lock poisoned").clone();
+
+    match (flag, std::env::var("TRUSTBLOCK_PROFILE")) {
+        (Some(profile), _) => (profile, Source::Flag),
+        (None, Ok(profile)) => (profile, Source::Env("TRUSTBLOCK_PROFILE")),
+        (None, Err(_)) => match config.default_profile.clone() {
+            Some(profile) => (profile, Source::DefaultProfile),
+            None => (DEFAULT_PROFILE.to_string(), Source::Default),
+        },
+    }
+}
+
+/// Configuration sources of the selected profile, from the highest to the
+/// lowest priority: environment variables, the config file,
+/// `~/.trustblock/.env` and the built-in profile.
+#[derive(Clone, Debug, Default)]
+pub struct Layers {
+    pub profile: String,
+    pub profile_source: Source,
+    pub file: Option<Profile>,
+    pub built_in: Option<Profile>,
+    dotenv: HashMap<String, String>,
+}
+
+impl Layers {
+    /// Loads `~/.trustblock/.env` and the config file, and selects the profile
+    /// with [`selected_profile`].
+    pub fn load() -> eyre::Result<Self> {
+        apply_dotenv()?;
+
+        let config = ConfigFile::load()?;
+
+        let (profile, profile_source) = selected_profile(&config);
+
+        let file = config.profiles.get(&profile).cloned();
+        let built_in = Profile::built_in(&profile);
+
+        if file.is_none() && built_in.is_none() {
+            return Err(eyre!(
+                "Unknown profile {profile:?}. Add a [profiles.{profile}] table to {:?}",
+                ConfigFile::path()?
+            ));
+        }
+
+        Ok(Self {
+            profile,
+            profile_source,
+            file,
+            built_in,
+            dotenv: dotenv_values(),
+        })
+    }
+
+    /// Returns the value of one of [`KEYS`] and where it comes from.
+    #[must_use]
+    pub fn lookup(&self, key: &str) -> Option<(String, Source)> {
+        if let Some(value) = FLAGS.lock().expect("flags lock poisoned").get(key) {
+            return Some((value.clone(), Source::Flag));
+        }
+
+        let env_var = self::key(key).and_then(|key| key.env_var);
+
+        // Values of `~/.trustblock/.env` are also in the process environment, but
+        // rank below the profile of the config file.
+        let mut dotenv_value = None;
+
+        if let Some(env_var) = env_var {
+            if let Some(value) = std::env::var(env_var)
+                .ok()
+                .filter(|value| !value.is_empty())
+            {
+                if self.dotenv.get(env_var) != Some(&value) {
+                    return Some((value, Source::Env(env_var)));
+                }
+
+                dotenv_value = Some((value, Source::DotEnv(env_var)));
+            }
+        }
+
+        if let Some(value) = self.file.as_ref().and_then(|profile| profile.get(key)) {
+            return Some((value.clone(), Source::Profile(self.profile.clone())));
+        }
+
+        if dotenv_value.is_some() {
+            return dotenv_value;
+        }
+
+        if let Some(value) = self.built_in.as_ref().and_then(|profile| profile.get(key)) {
+            return Some((value.clone(), Source::Default));
+        }
+
+        match key {
+            "api_url" => Some((PRODUCTION_API_URL.to_string(), Source::Default)),
+            "pdf_generate_endpoint" => Some((PDF_GENERATE_ENDPOINT.to_string(), Source::Default)),
+            "max_retries" => Some((
+                RetryPolicy::default().max_retries.to_string(),
+                Source::Default,
+            )),
+            "retry_backoff_ms" => Some((
+                RetryPolicy::default()
+                    .initial_backoff
+                    .as_millis()
+                    .to_string(),
+                Source::Default,
+            )),
+            "retry_max_backoff_ms" => Some((
+                RetryPolicy::default().max_backoff.as_millis().to_string(),
+                Source::Default,
+            )),
+            "retry_jitter" => Some((RetryPolicy::default().jitter.to_string(), Source::Default)),
+            "connect_timeout_secs" => Some((
+                HttpSettings::default()
+                    .connect_timeout
+                    .as_secs()
+                    .to_string(),
+                Source::Default,
+            )),
+            "timeout_secs" => Some((
+                HttpSettings::default().timeout.as_secs().to_string(),
+                Source::Default,
+            )),
+            "audit_idempotency_key" | "insecure" => Some((false.to_string(), Source::Default)),
+            "update_check" => Some((true.to_string(), Source::Default)),
+            "release_url" => Some((GITHUB_RELEASES.to_string(), Source::Default)),
+            "max_issues_per_severity" => {
+                Some((DEFAULT_MAX_ISSUES_PER_SEVERITY.to_string(), Source::Default))
+            }
+            "report_store" => Some(("web3storage".to_string(), Source::Default)),
+            "web3_storage_url" => Some((WEB3_STORAGE_API_URL.to_string(), Source::Default)),
+            "kubo_api_url" => Some((KUBO_API_URL.to_string(), Source::Default)),
+            "s3_region" => Some(("us-east-1".to_string(), Source::Default)),
+            key => {
+                let (_, path) = API_PATHS.iter().find(|(name, _)| *name == key)?;
+                let (api_url, source) = self.lookup("api_url")?;
+
+                Some((format!("{}/{path}", api_url.trim_end_matches('/')), source))
+            }
+        }
+    }
+
+    fn value(&self, key: &str) -> String {
+        self.optional(key).unwrap_or_default()
+    }
+
+    fn optional(&self, key: &str) -> Option<String> {
+        self.lookup(key).map(|(value, _)| value)
+    }
+
+    /// Parses a numeric or boolean key, failing with the source of an invalid
+    /// value.
+    fn parse<T: std::str::FromStr>(&self, key: &str) -> eyre::Result<Option<T>> {
+        self.lookup(key)
+            .map(|(value, source)| {
+                value
+                    .trim()
+                    .parse::<T>()
+                    .map_err(|_| eyre!("Invalid {key} {value:?} from {source}"))
+            })
+            .transpose()
+    }
+}
+
+/// Variables defined in `~/.trustblock/.env`, to tell them apart from the
+/// ones set in the environment.
+#[allow(deprecated)]
+#[must_use]
+pub fn dotenv_values() -> HashMap<String, String> {
+    let Some(home_dir) = dirs::home_dir() else {
+        return HashMap::new();
+    };
+
+    dotenv::from_path_iter(home_dir.join(CLI_PATH).join(".env"))
+        .map(|values| values.filter_map(Result::ok).collect())
+        .unwrap_or_default()
+}
+
+/// Removes a variable from `~/.trustblock/.env`, keeping the other lines.
+pub fn remove_dotenv_var(name: &str) -> eyre::Result<()> {
+    let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
+    let env_path = home_dir.join(CLI_PATH).join(".env");
+
+    if !env_path.exists() {
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(&env_path)?
+        .lines()
+        .filter(|line| {
+            line.trim_start()
+                .trim_start_matches("export ")
+                .split_once('=')
+                .is_none_or(|(key, _)| key.trim() != name)
+        })
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+
+    write_private(&env_path, &contents)
+}
+
+/// Settings of the selected profile, with the environment variable overrides
+/// applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub profile: String,
+    pub api_key: Option<String>,
+    pub api_key_source: Option<Source>,
+    pub audit_endpoint: String,
+    pub audit_report_hash_endpoint: String,
+    pub project_slug_endpoint: String,
+    pub web3_storage_api_endpoint: String,
+    pub pdf_generate_endpoint: String,
+    pub auth_endpoint: String,
+    pub retry: RetryPolicy,
+    /// Whether the audit endpoint honors
+    /// [`crate::retry::IDEMPOTENCY_KEY_HEADER`], which makes creating an
+    /// audit safe to retry.
+    pub audit_idempotency_key: bool,
+    pub http: HttpSettings,
+    /// Whether commands of the binary check for a new version.
+    pub update_check: bool,
+    /// Where `self-update` downloads releases from.
+    pub release_url: String,
+    /// Issues of a status and severity an audit can have, see
+    /// [`crate::validation::validate_audit`].
+    pub max_issues_per_severity: u32,
+    pub store: StoreSettings,
+}
+
+impl Settings {
+    /// Resolves the settings of the selected profile, see [`Layers::load`].
+    pub fn load() -> eyre::Result<Self> {
+        Self::from_layers(&Layers::load()?)
+    }
+
+    /// Resolves the settings of a profile that is not read from the config
+    /// file.
+    pub fn resolve(profile: String, settings: Profile) -> eyre::Result<Self> {
+        Self::from_layers(&Layers {
+            profile,
+            built_in: Some(settings),
+            ..Layers::default()
+        })
+    }
+
+    /// Fails when a numeric or boolean setting holds an invalid value.
+    pub fn from_layers(layers: &Layers) -> eyre::Result<Self> {
+        let api_key = layers.lookup("api_key");
+        let default_retry = RetryPolicy::default();
+        let default_http = HttpSettings::default();
+
+        Ok(Self {
+            profile: layers.profile.clone(),
+            api_key: api_key.clone().map(|(value, _)| value),
+            api_key_source: api_key.map(|(_, source)| source),
+            audit_endpoint: layers.value("audit_endpoint"),
+            audit_report_hash_endpoint: layers.value("audit_report_hash_endpoint"),
+            project_slug_endpoint: layers.value("project_slug_endpoint"),
+            web3_storage_api_endpoint: layers.value("web3_storage_api_endpoint"),
+            pdf_generate_endpoint: layers.value("pdf_generate_endpoint"),
+            auth_endpoint: layers.value("auth_endpoint"),
+            retry: RetryPolicy {
+                max_retries: layers
+                    .parse("max_retries")?
+                    .unwrap_or(default_retry.max_retries),
+                initial_backoff: layers
+                    .parse("retry_backoff_ms")?
+                    .map_or(default_retry.initial_backoff, Duration::from_millis),
+                max_backoff: layers
+                    .parse("retry_max_backoff_ms")?
+                    .map_or(default_retry.max_backoff, Duration::from_millis),
+                jitter: layers
+                    .parse("retry_jitter")?
+                    .unwrap_or(default_retry.jitter),
+            },
+            audit_idempotency_key: layers.parse("audit_idempotency_key")?.unwrap_or(false),
+            http: HttpSettings {
+                connect_timeout: layers
+                    .parse("connect_timeout_secs")?
+                    .map_or(default_http.connect_timeout, Duration::from_secs),
+                timeout: layers
+                    .parse("timeout_secs")?
+                    .map_or(default_http.timeout, Duration::from_secs),
+                proxy_url: layers.lookup("proxy_url").map(|(value, _)| value),
+                ca_bundle: layers.lookup("ca_bundle").map(|(value, _)| value.into()),
+                insecure: layers.parse("insecure")?.unwrap_or(default_http.insecure),
+            },
+            update_check: layers.parse("update_check")?.unwrap_or(true),
+            release_url: layers.value("release_url"),
+            max_issues_per_severity: layers
+                .parse("max_issues_per_severity")?
+                .unwrap_or(DEFAULT_MAX_ISSUES_PER_SEVERITY),
+            store: StoreSettings {
+                report_store: layers.value("report_store"),
+                web3_storage_url: layers.value("web3_storage_url"),
+                kubo_api_url: layers.value("kubo_api_url"),
+                kubo_api_auth: layers.optional("kubo_api_auth"),
+                ipfs_gateway_url: layers.optional("ipfs_gateway_url"),
+                s3_endpoint: layers.optional("s3_endpoint"),
+                s3_bucket: layers.optional("s3_bucket"),
+                s3_region: layers.value("s3_region"),
+                s3_access_key_id: layers.optional("s3_access_key_id"),
+                s3_secret_access_key: layers.optional("s3_secret_access_key"),
+                s3_prefix: layers.value("s3_prefix"),
+                s3_public_url: layers.optional("s3_public_url"),
+            },
+        })
+    }
+
+    /// Returns the API key given with `--api-key`, or the configured one,
+    /// decrypted if needed.
+    pub fn api_key(&self, flag: Option<String>) -> eyre::Result<String> {
+        if let Some(api_key) = flag {
+            return vault::reveal(&api_key);
+        }
+
+        let api_key = self.api_key.clone().ok_or_else(|| {
+            eyre::Report::new(VarError::NotPresent).wrap_err(format!(
+                "API key is not set for profile {:?}. Pass --api-key, set API_KEY or run `trustblock config set api_key <API_KEY>`",
+                self.profile
+            ))
+        })?;
+
+        vault::reveal(&api_key)
+    }
+
+    /// Returns whether the API key is stored unencrypted in the config file or
+    /// in `~/.trustblock/.env`.
+    #[must_use]
+    pub fn api_key_in_plain_text(&self) -> bool {
+        self.api_key
+            .as_deref()
+            .is_some_and(|api_key| !vault::is_encrypted(api_key))
+            && matches!(
+                self.api_key_source,
+                Some(Source::DotEnv(_) | Source::Profile(_))
+            )
+    }
+
+    /// Warnings about the profile, returned to the caller instead of being
+    /// printed.
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.api_key_in_plain_text() {
+            warnings.push(
+                "The API key is stored in plain text. Run `trustblock config encrypt` to encrypt it"
+                    .to_string(),
+            );
+        }
+
+        warnings
+    }
+}
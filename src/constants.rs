@@ -1,8 +1,8 @@
-pub const AUDIT_ENDPOINT: &str = "https://beta.trustblock.run/api/audit/";
+pub const PRODUCTION_API_URL: &str = "https://beta.trustblock.run/api";
 
-pub const PROJECT_SLUG_ENDPOINT: &str = "https://beta.trustblock.run/api/project/";
+pub const STAGING_API_URL: &str = "https://staging.trustblock.run/api";
 
-pub const WEB3_STORAGE_API_ENDPOINT: &str = "https://beta.trustblock.run/api/file/";
+pub const LOCAL_API_URL: &str = "http://localhost:3000/api";
 
 pub const PDF_GENERATE_ENDPOINT: &str = "https://trustblock-pdf-production.up.railway.app/pdf";
 
@@ -11,6 +11,12 @@ pub const CRATES_API_RELEASE_ENDPOINT: &str = "https://crates.io/api/v1/crates/t
 pub const GITHUB_LATEST_RELEASE: &str =
     "https://github.com/Trustblock-Inc/trustblock-cli/releases/latest";
 
+pub const GITHUB_RELEASES: &str = "https://github.com/Trustblock-Inc/trustblock-cli/releases";
+
+pub const KUBO_API_URL: &str = "http://127.0.0.1:5001";
+
+pub const WEB3_STORAGE_API_URL: &str = "https://api.web3.storage";
+
 pub const WEB3_STORAGE_ENDPOINT: &str = ".ipfs.w3s.link";
 
 pub const TRUSTBLOCK_API_KEY_HEADER: &str = "x-trustblock-api-key";
@@ -0,0 +1,230 @@
+use std::{env::VarError, fmt::Write, time::Duration};
+
+use reqwest::{Response, StatusCode};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{retry::retry_after, validation::FieldError};
+
+/// Exit codes of the `trustblock` binary. Values are stable across releases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Failure = 1,
+    InvalidInput = 2,
+    Authentication = 3,
+    Network = 4,
+    Duplicate = 5,
+    ServerRejected = 6,
+    Mismatch = 7,
+}
+
+/// Exit code table shown in `--help`.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Unexpected failure
+  2  Invalid input: arguments, audit data, report file or missing API key
+  3  Authentication failed
+  4  Network failure
+  5  Audit or project already published
+  6  Rejected by Trustblock or the report store: server error, rate limiting or other rejection
+  7  Report does not match the published report";
+
+impl ExitCode {
+    /// Classifies an error report by the first known error type in its chain.
+    #[must_use]
+    pub fn from_report(report: &eyre::Report) -> Self {
+        report
+            .chain()
+            .find_map(|error| {
+                if let Some(error) = error.downcast_ref::<TrustblockError>() {
+                    return Some(error.exit_code());
+                }
+
+                if error.is::<reqwest::Error>() {
+                    return Some(Self::Network);
+                }
+
+                if error.is::<serde_json::Error>()
+                    || error.is::<std::io::Error>()
+                    || error.is::<VarError>()
+                {
+                    return Some(Self::InvalidInput);
+                }
+
+                None
+            })
+            .unwrap_or(Self::Failure)
+    }
+}
+
+const DUPLICATE_REPORT_ERROR: &str = "Report hash is not a unique value.";
+
+const DUPLICATE_PROJECT_ERROR: &str = "Project domain is not a unique value.";
+
+/// Errors reported by Trustblock, parsed from the API response status and body.
+///
+/// Failed uploads to the report store are reported as
+/// [`TrustblockError::StorageRejected`].
+///
+/// Invalid audit data found locally is reported as
+/// [`TrustblockError::Validation`] as well, and a wrong passphrase for the
+/// encrypted API key as [`TrustblockError::WrongPassphrase`].
+#[derive(Debug, Error)]
+pub enum TrustblockError {
+    #[error("Audit already published: report hash is not a unique value")]
+    DuplicateReport,
+
+    #[error("Project already exists: project domain is not a unique value")]
+    DuplicateProject,
+
+    #[error("Authentication failed: {message}. Check your API key")]
+    Unauthorized { message: String },
+
+    #[error("Could not decrypt the API key: wrong passphrase")]
+    WrongPassphrase,
+
+    #[error("{}", format_validation(message, fields))]
+    Validation {
+        message: String,
+        fields: Vec<FieldError>,
+    },
+
+    #[error("Rate limited by Trustblock API{}", format_retry_after(retry_after))]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("Trustblock API server error ({status}): {message}")]
+    Server { status: StatusCode, message: String },
+
+    #[error("Request rejected by Trustblock API ({status}): {message}")]
+    Rejected { status: StatusCode, message: String },
+
+    #[error("Report upload rejected by {store} ({status}): {message}")]
+    StorageRejected {
+        store: &'static str,
+        status: StatusCode,
+        message: String,
+    },
+}
+
+impl TrustblockError {
+    #[must_use]
+    pub const fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::DuplicateReport | Self::DuplicateProject => ExitCode::Duplicate,
+            Self::Unauthorized { .. } | Self::WrongPassphrase => ExitCode::Authentication,
+            Self::Validation { .. } => ExitCode::InvalidInput,
+            Self::RateLimited { .. }
+            | Self::Server { .. }
+            | Self::Rejected { .. }
+            | Self::StorageRejected { .. } => ExitCode::ServerRejected,
+        }
+    }
+
+    /// Consumes an unsuccessful response and classifies it.
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status();
+
+        let retry_after = retry_after(response.headers());
+
+        let text = response.text().await.unwrap_or_default();
+        let body = serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text));
+
+        Self::from_parts(status, &body, retry_after)
+    }
+
+    /// Classifies an unsuccessful response from its status, JSON body and
+    /// `Retry-After` header.
+    #[must_use]
+    pub fn from_parts(status: StatusCode, body: &Value, retry_after: Option<Duration>) -> Self {
+        let message = error_message(body);
+
+        match status {
+            StatusCode::BAD_REQUEST | StatusCode::CONFLICT if message == DUPLICATE_REPORT_ERROR => {
+                Self::DuplicateReport
+            }
+            StatusCode::BAD_REQUEST | StatusCode::CONFLICT
+                if message == DUPLICATE_PROJECT_ERROR =>
+            {
+                Self::DuplicateProject
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Unauthorized { message },
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Self::Validation {
+                message,
+                fields: field_errors(body),
+            },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { retry_after },
+            status if status.is_server_error() => Self::Server { status, message },
+            status => Self::Rejected { status, message },
+        }
+    }
+}
+
+/// Extracts the error message from `{"error": ...}` or `{"message": ...}`
+/// bodies, falling back to the raw body.
+fn error_message(body: &Value) -> String {
+    let message = match &body["error"] {
+        Value::Null => &body["message"],
+        error => error,
+    };
+
+    match message {
+        Value::String(message) => message.clone(),
+        Value::Null => match body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        },
+        message => message.to_string(),
+    }
+}
+
+/// Extracts per field details, accepting both `"path": "a.b"` and
+/// `"path": ["a", "b"]` entries.
+fn field_errors(body: &Value) -> Vec<FieldError> {
+    let Some(details) = body["details"].as_array() else {
+        return Vec::new();
+    };
+
+    details
+        .iter()
+        .map(|detail| {
+            let path = match &detail["path"] {
+                Value::Array(segments) => segments
+                    .iter()
+                    .map(|segment| match segment {
+                        Value::String(segment) => segment.clone(),
+                        segment => segment.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("."),
+                Value::String(path) => path.clone(),
+                _ => String::new(),
+            };
+
+            FieldError {
+                path,
+                message: detail["message"].as_str().unwrap_or_default().to_string(),
+            }
+        })
+        .collect()
+}
+
+fn format_validation(message: &str, fields: &[FieldError]) -> String {
+    if fields.is_empty() {
+        return message.to_string();
+    }
+
+    fields
+        .iter()
+        .fold(format!("{message}:"), |mut output, field| {
+            let _ = write!(output, "\n  {field}");
+            output
+        })
+}
+
+fn format_retry_after(retry_after: &Option<Duration>) -> String {
+    retry_after
+        .map(|retry_after| format!(". Retry after {}s", retry_after.as_secs()))
+        .unwrap_or_default()
+}
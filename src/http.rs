@@ -0,0 +1,93 @@
+use std::{path::PathBuf, time::Duration};
+
+use eyre::eyre;
+use reqwest::{Certificate, Client, ClientBuilder, Proxy};
+
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+/// Settings of the HTTP client every request is sent with.
+///
+/// Without `proxy_url`, the `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY`
+/// environment variables are honored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpSettings {
+    pub connect_timeout: Duration,
+    /// Maximum time of an API request, from connecting to reading the whole
+    /// response. Uploads and downloads are not limited, see
+    /// [`HttpSettings::transfer_client`].
+    pub timeout: Duration,
+    pub proxy_url: Option<String>,
+    /// PEM file of root certificates trusted in addition to the system ones.
+    pub ca_bundle: Option<PathBuf>,
+    /// Accepts invalid TLS certificates, for local testing only.
+    pub insecure: bool,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(120),
+            proxy_url: None,
+            ca_bundle: None,
+            insecure: false,
+        }
+    }
+}
+
+impl HttpSettings {
+    /// Builds an HTTP client with these settings.
+    pub fn client(&self) -> eyre::Result<Client> {
+        Ok(self.builder()?.timeout(self.timeout).build()?)
+    }
+
+    /// Builds an HTTP client for uploads and downloads, whose duration depends
+    /// on the file size: only connecting is limited in time.
+    pub fn transfer_client(&self) -> eyre::Result<Client> {
+        Ok(self.builder()?.build()?)
+    }
+
+    fn builder(&self) -> eyre::Result<ClientBuilder> {
+        let mut builder = Client::builder().connect_timeout(self.connect_timeout);
+
+        if let Some(proxy_url) = &self.proxy_url {
+            builder = builder.proxy(Proxy::all(proxy_url).map_err(|error| {
+                eyre::Report::new(error).wrap_err(format!("Invalid proxy URL {proxy_url:?}"))
+            })?);
+        }
+
+        if let Some(ca_bundle) = &self.ca_bundle {
+            for certificate in certificates(ca_bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        if self.insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Reads the certificates of a PEM bundle.
+fn certificates(path: &PathBuf) -> eyre::Result<Vec<Certificate>> {
+    let pem = std::fs::read_to_string(path).map_err(|error| {
+        eyre::Report::new(error).wrap_err(format!("Could not read CA bundle {path:?}"))
+    })?;
+
+    let certificates = pem
+        .split_inclusive(PEM_CERTIFICATE_END)
+        .filter(|block| block.contains(PEM_CERTIFICATE_END))
+        .map(|block| Certificate::from_pem(block.trim().as_bytes()))
+        .collect::<Result<Vec<Certificate>, reqwest::Error>>()
+        .map_err(|error| {
+            eyre::Report::new(error).wrap_err(format!("Invalid certificate in {path:?}"))
+        })?;
+
+    if certificates.is_empty() {
+        return Err(eyre!("No certificate found in CA bundle {path:?}"));
+    }
+
+    Ok(certificates)
+}
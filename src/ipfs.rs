@@ -0,0 +1,144 @@
+use std::{fs::File, io::Read, path::Path};
+
+use cid::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
+
+/// Chunk size used when web3.storage imports an uploaded file.
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of links per node of the balanced UnixFS DAG.
+pub const MAX_CHILDREN: usize = 1024;
+
+const RAW_CODEC: u64 = 0x55;
+
+const DAG_PB_CODEC: u64 = 0x70;
+
+/// UnixFS `Data.Type` of a file node.
+const UNIXFS_FILE_TYPE: u64 = 2;
+
+/// A node of the DAG: its CID, the file bytes below it and the cumulative
+/// size of all blocks below it including itself.
+#[derive(Debug, Clone, Copy)]
+struct DagNode {
+    cid: Cid,
+    file_size: u64,
+    total_size: u64,
+}
+
+/// Computes the IPFS CID web3.storage assigns to the file at `path`, without
+/// uploading it.
+///
+/// The file is imported as CIDv1 with raw leaves of [`CHUNK_SIZE`] bytes,
+/// hashed with SHA-256 and linked in a balanced DAG of at most
+/// [`MAX_CHILDREN`] links per node.
+pub fn file_cid(path: &Path) -> eyre::Result<String> {
+    let mut file = File::open(path)?;
+
+    let mut leaves = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    loop {
+        let length = read_chunk(&mut file, &mut chunk)?;
+
+        if length == 0 && !leaves.is_empty() {
+            break;
+        }
+
+        let size = length as u64;
+
+        leaves.push(DagNode {
+            cid: Cid::new_v1(RAW_CODEC, Code::Sha2_256.digest(&chunk[..length])),
+            file_size: size,
+            total_size: size,
+        });
+
+        if length < CHUNK_SIZE {
+            break;
+        }
+    }
+
+    let mut level = leaves;
+
+    while level.len() > 1 {
+        level = level.chunks(MAX_CHILDREN).map(parent_node).collect();
+    }
+
+    Ok(level[0].cid.to_string())
+}
+
+/// Whether two CIDs address the same content, whatever their version and
+/// multibase: CIDv0 `Qm...` and CIDv1 `bafy...` of a report share their
+/// multihash.
+pub fn same_content(cid: &str, other: &str) -> eyre::Result<bool> {
+    Ok(parse_cid(cid)?.hash() == parse_cid(other)?.hash())
+}
+
+fn parse_cid(cid: &str) -> eyre::Result<Cid> {
+    Cid::try_from(cid.trim())
+        .map_err(|error| eyre::Report::new(error).wrap_err(format!("Invalid CID {cid:?}")))
+}
+
+/// Fills `chunk` as far as possible, returning the number of bytes read.
+fn read_chunk(file: &mut File, chunk: &mut [u8]) -> std::io::Result<usize> {
+    let mut length = 0;
+
+    while length < chunk.len() {
+        match file.read(&mut chunk[length..])? {
+            0 => break,
+            read => length += read,
+        }
+    }
+
+    Ok(length)
+}
+
+/// Builds the dag-pb UnixFS file node linking `children`.
+fn parent_node(children: &[DagNode]) -> DagNode {
+    let file_size = children.iter().map(|child| child.file_size).sum();
+
+    let mut data = Vec::new();
+    encode_varint_field(&mut data, 1, UNIXFS_FILE_TYPE);
+    encode_varint_field(&mut data, 3, file_size);
+    for child in children {
+        encode_varint_field(&mut data, 4, child.file_size);
+    }
+
+    // dag-pb canonical form: links first, then data
+    let mut node = Vec::new();
+    for child in children {
+        let mut link = Vec::new();
+        encode_bytes_field(&mut link, 1, &child.cid.to_bytes());
+        encode_bytes_field(&mut link, 2, &[]);
+        encode_varint_field(&mut link, 3, child.total_size);
+
+        encode_bytes_field(&mut node, 2, &link);
+    }
+    encode_bytes_field(&mut node, 1, &data);
+
+    DagNode {
+        cid: Cid::new_v1(DAG_PB_CODEC, Code::Sha2_256.digest(&node)),
+        file_size,
+        total_size: node.len() as u64 + children.iter().map(|child| child.total_size).sum::<u64>(),
+    }
+}
+
+fn encode_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn encode_varint_field(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    encode_varint(buffer, field << 3);
+    encode_varint(buffer, value);
+}
+
+fn encode_bytes_field(buffer: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    encode_varint(buffer, (field << 3) | 2);
+    encode_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use eyre::ContextCompat;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{client::ReportSource, constants::CLI_PATH, utils::sha256_file};
+
+/// Progress of a single publish, persisted under `~/.trustblock/journal/` so
+/// a failed publish can be resumed without repeating completed steps.
+///
+/// The default journal is only kept in memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishJournal {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    pub audit_file: PathBuf,
+    pub project_resolved: bool,
+    pub project_id: Option<String>,
+    pub report_cid: Option<String>,
+    pub report_url: Option<String>,
+}
+
+impl PublishJournal {
+    /// Opens the journal of an audit file and report. Previously recorded steps
+    /// are only loaded when `resume` is set, otherwise the publish starts over.
+    ///
+    /// The journal is keyed on the path of the audit file rather than its
+    /// content, so it survives fixing the file after the API rejected it.
+    pub fn open(audit_file: &Path, report: &ReportSource, resume: bool) -> eyre::Result<Self> {
+        let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
+
+        let journal_dir = home_dir.join(CLI_PATH).join("journal");
+
+        Self::open_in(&journal_dir, audit_file, report, resume)
+    }
+
+    /// Same as [`PublishJournal::open`] with an explicit journal directory.
+    pub fn open_in(
+        journal_dir: &Path,
+        audit_file: &Path,
+        report: &ReportSource,
+        resume: bool,
+    ) -> eyre::Result<Self> {
+        let report_key = match report {
+            ReportSource::Pdf(path) => sha256_file(path)?,
+            ReportSource::Url(url) => format!("{:x}", Sha256::digest(url.as_bytes())),
+        };
+
+        let audit_path = std::fs::canonicalize(audit_file)?;
+
+        let key = Sha256::new()
+            .chain_update(audit_path.to_string_lossy().as_bytes())
+            .chain_update(report_key)
+            .finalize();
+
+        let path = journal_dir.join(format!("{key:x}.json"));
+
+        if resume && path.exists() {
+            let journal = serde_json::from_str::<Self>(&std::fs::read_to_string(&path)?)?;
+
+            return Ok(Self {
+                path: Some(path),
+                ..journal
+            });
+        }
+
+        Ok(Self {
+            path: Some(path),
+            audit_file: audit_file.to_path_buf(),
+            ..Self::default()
+        })
+    }
+
+    /// Returns the recorded report CID and URL if the upload step completed.
+    #[must_use]
+    pub fn uploaded_report(&self) -> Option<(String, String)> {
+        self.report_cid.clone().zip(self.report_url.clone())
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(journal_dir) = path.parent() {
+            std::fs::create_dir_all(journal_dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Deletes the journal once there is nothing left to resume.
+    pub fn remove(&self) -> eyre::Result<()> {
+        if let Some(path) = self.path.as_ref().filter(|path| path.exists()) {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,20 @@
+pub mod client;
+#[cfg(feature = "cli")]
 pub mod cmd;
+pub mod config;
 pub mod constants;
+pub mod error;
+#[cfg(feature = "cli")]
 pub mod error_handler;
+pub mod http;
+pub mod ipfs;
+pub mod journal;
+pub mod retry;
+pub mod shell;
+pub mod store;
 pub mod types;
+#[cfg(feature = "cli")]
+pub mod update;
 pub mod utils;
+pub mod validation;
+pub mod vault;
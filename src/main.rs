@@ -1,35 +1,101 @@
 use clap::Parser;
 use trustblock_cli::{
     cmd::{
-        block_on, check_update,
+        block_on,
         trustblock::{Cli, Commands},
-        Cmd,
+        Cmd, UpdateCheck,
     },
+    config::Settings,
     constants::CLI_PATH,
-    error_handler,
+    error::ExitCode,
+    error_handler, sh_println,
+    shell::{self, CommandOutput},
 };
 
-fn main() -> eyre::Result<()> {
-    error_handler::install()?;
+fn main() {
+    if let Err(error) = error_handler::install() {
+        eprintln!("Error: {error:?}");
+        std::process::exit(ExitCode::Failure as i32);
+    }
+
     let cli = Cli::parse();
 
-    block_on(check_update())?;
+    shell::set_output_format(cli.output);
+    cli.apply_global_flags();
+
+    let command = cli.command.name();
+
+    if Settings::load().is_ok_and(|settings| settings.http.insecure) {
+        eprintln!("Warning: TLS certificate verification is disabled\n");
+    }
+
+    // A notice about the version being installed would be noise
+    let update_check = match cli.command {
+        Commands::SelfUpdate(_) => None,
+        _ => UpdateCheck::spawn(),
+    };
+
+    let result = run(cli.command).and_then(|output| {
+        output.emit()?;
+        Ok(output.exit_code)
+    });
+
+    if let Some(update_check) = update_check {
+        update_check.notify();
+    }
+
+    match result {
+        Ok(Some(exit_code)) => std::process::exit(exit_code),
+        Ok(None) => (),
+        Err(error) => {
+            eprintln!("Error: {error:?}");
 
-    match cli.command {
+            if shell::is_json() {
+                let _ = CommandOutput::error(command, &error).emit();
+            }
+
+            std::process::exit(ExitCode::from_report(&error) as i32);
+        }
+    }
+}
+
+fn run(command: Commands) -> eyre::Result<CommandOutput> {
+    match command {
         Commands::PublishAudit(cmd) => {
-            println!("Publishing an audit\n");
-            block_on(cmd.run())?;
-            Ok(())
+            sh_println!("Publishing an audit\n");
+            block_on(cmd.run())
+        }
+        Commands::PublishBatch(cmd) => {
+            sh_println!("Publishing audits from a manifest\n");
+            block_on(cmd.run())
+        }
+        Commands::Validate(cmd) => {
+            sh_println!("Validating audit data\n");
+            cmd.run()
+        }
+        Commands::VerifyReport(cmd) => {
+            sh_println!("Verifying audit report\n");
+            block_on(cmd.run())
+        }
+        Commands::Config(cmd) => cmd.run(),
+        Commands::Login(cmd) => block_on(cmd.run()),
+        Commands::Logout(cmd) => block_on(cmd.run()),
+        Commands::Whoami(cmd) => block_on(cmd.run()),
+        Commands::Doctor(cmd) => {
+            sh_println!("Diagnosing the local setup\n");
+            block_on(cmd.run())
+        }
+        Commands::SelfUpdate(cmd) => {
+            sh_println!("Updating Trustblock CLI\n");
+            block_on(cmd.run())
         }
         Commands::Init(cmd) => {
-            println!("Generating {CLI_PATH} folder...\n");
-            cmd.run()?;
-            Ok(())
+            sh_println!("Generating {CLI_PATH} folder...\n");
+            cmd.run()
         }
         Commands::Clean(cmd) => {
-            println!("Cleaning {CLI_PATH} folder...\n");
-            cmd.run()?;
-            Ok(())
+            sh_println!("Cleaning {CLI_PATH} folder...\n");
+            cmd.run()
         }
     }
 }
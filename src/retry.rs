@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    RequestBuilder, Response, StatusCode,
+};
+
+use crate::sh_println;
+
+/// Header of a key the server processes requests with once, which makes a
+/// non-idempotent request safe to retry when the server honors it.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Statuses of transient failures that are worth retrying.
+const RETRY_STATUSES: [StatusCode; 6] = [
+    StatusCode::REQUEST_TIMEOUT,
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::INTERNAL_SERVER_ERROR,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// How failed requests are retried: exponential backoff starting at
+/// `initial_backoff`, doubled on each retry up to `max_backoff`.
+///
+/// With `jitter`, each delay is picked at random between half and all of the
+/// backoff, so clients failing together do not retry together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt, 0 disables retries.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends each request once.
+    #[must_use]
+    pub fn never() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the given retry, starting at 0.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            backoff
+        }
+    }
+
+    /// Sends the request built by `request`, which is called again for each
+    /// attempt, and returns the last response.
+    ///
+    /// Connection failures, timeouts and the statuses of transient failures
+    /// are retried, waiting for the `Retry-After` of 429 and 503 responses.
+    /// When the server asks to wait longer than `max_backoff`, the response
+    /// is returned instead. Only requests the caller marks as `idempotent`,
+    /// which the server handles the same when repeated, are retried.
+    pub async fn send(
+        &self,
+        idempotent: bool,
+        request: impl Fn() -> RequestBuilder,
+    ) -> reqwest::Result<Response> {
+        let mut retry = 0;
+
+        loop {
+            let (client, built) = request().build_split();
+            let built = built?;
+
+            let retryable = idempotent && retry < self.max_retries;
+            let url = built.url().clone();
+
+            let delay = match client.execute(built).await {
+                Ok(response) if retryable && RETRY_STATUSES.contains(&response.status()) => {
+                    let delay = match response.status() {
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                            retry_after(response.headers())
+                        }
+                        _ => None,
+                    };
+
+                    sh_println!("[!] {url} responded {}", response.status());
+
+                    match delay {
+                        Some(delay) if delay > self.max_backoff => {
+                            sh_println!(
+                                "[!] Not retrying, {url} asks to wait {}s",
+                                delay.as_secs()
+                            );
+
+                            return Ok(response);
+                        }
+                        Some(delay) => delay,
+                        None => self.backoff(retry),
+                    }
+                }
+                Err(error) if retryable && (error.is_connect() || error.is_timeout()) => {
+                    sh_println!("[!] Request to {url} failed: {error}");
+
+                    self.backoff(retry)
+                }
+                result => return result,
+            };
+
+            retry += 1;
+
+            sh_println!(
+                "[!] Retrying in {:.1}s ({retry}/{})",
+                delay.as_secs_f64(),
+                self.max_retries
+            );
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Parses `Retry-After`, either a number of seconds or an HTTP date.
+#[must_use]
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+    Some(
+        date.with_timezone(&Utc)
+            .signed_duration_since(Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
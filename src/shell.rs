@@ -0,0 +1,161 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::ExitCode;
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Format of the command results printed on stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum OutputFormat {
+    /// Human readable messages
+    #[default]
+    Text,
+    /// One JSON result object per command, human readable messages go to stderr
+    Json,
+}
+
+pub fn set_output_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Prints a human readable message: to stdout in text mode, to stderr in JSON
+/// mode so stdout only carries the result object.
+#[cfg(feature = "cli")]
+#[macro_export]
+macro_rules! sh_println {
+    ($($arg:tt)*) => {
+        if $crate::shell::is_json() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Progress messages of the library are only printed by the binary.
+#[cfg(not(feature = "cli"))]
+#[macro_export]
+macro_rules! sh_println {
+    ($($arg:tt)*) => {
+        let _ = format_args!($($arg)*);
+    };
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultStatus {
+    #[default]
+    Success,
+    Error,
+}
+
+/// A setting printed by the `config` command.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SettingOutput {
+    pub key: String,
+    pub value: Option<String>,
+    pub source: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A check of the `doctor` command, with the fix to apply when it does not
+/// pass.
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckOutput {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+/// Structured result of a command, printed on stdout with `--output json`.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutput {
+    pub command: &'static str,
+    pub status: ResultStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_cid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<CommandOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub settings: Vec<SettingOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<CheckOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl CommandOutput {
+    #[must_use]
+    pub fn new(command: &'static str) -> Self {
+        Self {
+            command,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn error(command: &'static str, report: &eyre::Report) -> Self {
+        Self {
+            command,
+            status: ResultStatus::Error,
+            error: Some(format!("{report:#}")),
+            exit_code: Some(ExitCode::from_report(report) as i32),
+            ..Self::default()
+        }
+    }
+
+    /// Records a warning and prints it as a human readable message.
+    pub fn warn(&mut self, warning: impl Into<String>) {
+        let warning = warning.into();
+        sh_println!("Warning: {warning}\n");
+        self.warnings.push(warning);
+    }
+
+    /// Prints the result object on stdout in JSON mode, does nothing in text
+    /// mode.
+    pub fn emit(&self) -> eyre::Result<()> {
+        if is_json() {
+            println!("{}", serde_json::to_string(self)?);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use eyre::ContextCompat;
+use reqwest::{
+    header::AUTHORIZATION,
+    multipart::{Form, Part},
+};
+use serde_json::Value;
+
+use super::{ReportStore, StoreSettings};
+use crate::{
+    client::TrustblockClient, constants::WEB3_STORAGE_ENDPOINT, ipfs::CHUNK_SIZE, sh_println, vault,
+};
+
+/// Adds and pins the report on a Kubo (go-ipfs) node through its HTTP API.
+///
+/// Reports are chunked like web3.storage does, but Kubo links at most 174
+/// blocks per DAG node instead of [`crate::ipfs::MAX_CHILDREN`], so the CID
+/// only matches [`crate::ipfs::file_cid`] for reports of up to 174 chunks.
+#[derive(Debug, Clone)]
+pub struct KuboStore {
+    pub api_url: String,
+    pub authorization: Option<String>,
+    pub gateway_url: Option<String>,
+}
+
+impl KuboStore {
+    /// Reads `kubo_api_url`, `kubo_api_auth` and `ipfs_gateway_url`.
+    #[must_use]
+    pub fn from_settings(settings: &StoreSettings) -> Self {
+        Self {
+            api_url: settings.kubo_api_url.clone(),
+            authorization: settings.kubo_api_auth.clone(),
+            gateway_url: settings.ipfs_gateway_url.clone(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl ReportStore for KuboStore {
+    fn name(&self) -> &'static str {
+        "kubo"
+    }
+
+    fn report_url(&self, cid: &str) -> String {
+        match &self.gateway_url {
+            Some(gateway_url) => format!("{}/ipfs/{cid}", gateway_url.trim_end_matches('/')),
+            None => format!("https://{cid}{WEB3_STORAGE_ENDPOINT}"),
+        }
+    }
+
+    async fn upload(
+        &self,
+        client: &TrustblockClient,
+        report_file_path: &Path,
+    ) -> eyre::Result<(String, String)> {
+        let file_name = report_file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .wrap_err("Invalid File Path")?
+            .to_string();
+
+        sh_println!("[+] Adding {file_name} to IPFS node {}", self.api_url);
+
+        let authorization = self
+            .authorization
+            .as_deref()
+            .map(vault::reveal)
+            .transpose()?;
+
+        let body = std::fs::read(report_file_path)?;
+
+        // Adding content addressed data again is a no-op, so the request is
+        // safe to retry
+        let response = client
+            .settings()
+            .retry
+            .send(true, || {
+                let part = Part::bytes(body.clone()).file_name(file_name.clone());
+
+                let request = client
+                    .transfer_http()
+                    .post(format!("{}/api/v0/add", self.api_url.trim_end_matches('/')))
+                    .query(&[
+                        ("cid-version", "1"),
+                        ("raw-leaves", "true"),
+                        ("chunker", &format!("size-{CHUNK_SIZE}")),
+                        ("pin", "true"),
+                    ])
+                    .multipart(Form::new().part("file", part));
+
+                match &authorization {
+                    Some(authorization) => request.header(AUTHORIZATION, authorization),
+                    None => request,
+                }
+            })
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        let cid = response["Hash"]
+            .as_str()
+            .wrap_err("IPFS node did not return the report CID")?
+            .to_string();
+
+        sh_println!("[+] Uploading is done\n");
+
+        Ok((cid.clone(), self.report_url(&cid)))
+    }
+}
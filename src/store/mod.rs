@@ -0,0 +1,80 @@
+mod kubo;
+mod s3;
+mod web3_storage;
+
+use std::{env::VarError, path::Path};
+
+use async_trait::async_trait;
+use eyre::eyre;
+pub use kubo::KuboStore;
+pub use s3::S3Store;
+pub use web3_storage::Web3StorageStore;
+
+use crate::client::TrustblockClient;
+
+/// Storage backend the audit report PDF is uploaded to.
+///
+/// The backend is picked with the `report_store` setting of the profile when
+/// the client is built, see [`StoreSettings::store`].
+#[async_trait(?Send)]
+pub trait ReportStore: Send + Sync {
+    /// Name of the backend, as set in `report_store`.
+    fn name(&self) -> &'static str;
+
+    /// Public URL the report with the given CID is served from once uploaded.
+    fn report_url(&self, cid: &str) -> String;
+
+    /// Uploads the report and returns its CID and public URL.
+    async fn upload(
+        &self,
+        client: &TrustblockClient,
+        report_file_path: &Path,
+    ) -> eyre::Result<(String, String)>;
+}
+
+/// Report store settings of a profile.
+///
+/// Secrets may be encrypted, they are only decrypted when uploading.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoreSettings {
+    /// `web3storage`, `kubo` or `s3`.
+    pub report_store: String,
+    pub web3_storage_url: String,
+    pub kubo_api_url: String,
+    /// `Authorization` header value of the Kubo API.
+    pub kubo_api_auth: Option<String>,
+    pub ipfs_gateway_url: Option<String>,
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+    pub s3_prefix: String,
+    pub s3_public_url: Option<String>,
+}
+
+impl StoreSettings {
+    /// Builds the configured report store, defaulting to web3.storage with an
+    /// upload token issued by Trustblock.
+    pub fn store(&self) -> eyre::Result<Box<dyn ReportStore>> {
+        match self.report_store.trim().to_lowercase().as_str() {
+            "" | "web3storage" | "web3.storage" => {
+                Ok(Box::new(Web3StorageStore::from_settings(self)))
+            }
+            "kubo" | "ipfs" => Ok(Box::new(KuboStore::from_settings(self))),
+            "s3" => Ok(Box::new(S3Store::from_settings(self)?)),
+            store => Err(eyre!(
+                "Unknown report store {store:?}. Use web3storage, kubo or s3"
+            )),
+        }
+    }
+}
+
+/// Returns a setting that is required by the configured report store.
+fn required(value: Option<&String>, key: &str) -> eyre::Result<String> {
+    value.cloned().ok_or_else(|| {
+        eyre::Report::new(VarError::NotPresent).wrap_err(format!(
+            "{key} is not set. Run `trustblock config set {key} <VALUE>`"
+        ))
+    })
+}
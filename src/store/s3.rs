@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use eyre::ContextCompat;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Url};
+use sha2::{Digest, Sha256};
+
+use super::{required, ReportStore, StoreSettings};
+use crate::{client::TrustblockClient, error::TrustblockError, ipfs::file_cid, sh_println, vault};
+
+/// Uploads the report to an S3 compatible bucket, such as AWS S3 or MinIO,
+/// with path style requests signed with AWS Signature Version 4.
+///
+/// Objects are stored under `<prefix><cid>.pdf`, the CID being computed
+/// locally with [`crate::ipfs::file_cid`].
+#[derive(Debug, Clone)]
+pub struct S3Store {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub prefix: String,
+    pub public_url: Option<String>,
+}
+
+impl S3Store {
+    /// Reads `s3_endpoint`, `s3_bucket`, `s3_access_key_id` and
+    /// `s3_secret_access_key`, and the optional `s3_region`, `s3_prefix` and
+    /// `s3_public_url`.
+    pub fn from_settings(settings: &StoreSettings) -> eyre::Result<Self> {
+        Ok(Self {
+            endpoint: required(settings.s3_endpoint.as_ref(), "s3_endpoint")?,
+            bucket: required(settings.s3_bucket.as_ref(), "s3_bucket")?,
+            region: settings.s3_region.clone(),
+            access_key_id: required(settings.s3_access_key_id.as_ref(), "s3_access_key_id")?,
+            secret_access_key: required(
+                settings.s3_secret_access_key.as_ref(),
+                "s3_secret_access_key",
+            )?,
+            prefix: settings.s3_prefix.clone(),
+            public_url: settings.s3_public_url.clone(),
+        })
+    }
+
+    fn object_key(&self, cid: &str) -> String {
+        format!("{}{cid}.pdf", self.prefix)
+    }
+
+    /// Builds the `Authorization` header value of a request. `headers` are the
+    /// lowercase names and values of the signed headers, sorted by name.
+    #[must_use]
+    pub fn authorization(
+        &self,
+        method: &str,
+        canonical_uri: &str,
+        headers: &[(&str, &str)],
+        payload_hash: &str,
+        amz_date: &str,
+    ) -> String {
+        let date = &amz_date[..8];
+
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect::<String>();
+
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{method}\n{canonical_uri}\n\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+        );
+
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{:x}",
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let signing_key = [date, &self.region, "s3", "aws4_request"].iter().fold(
+            format!("AWS4{}", self.secret_access_key).into_bytes(),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+
+        let signature = hmac_sha256(&signing_key, string_to_sign.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key_id
+        )
+    }
+}
+
+#[async_trait(?Send)]
+impl ReportStore for S3Store {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn report_url(&self, cid: &str) -> String {
+        let base_url = self
+            .public_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}", self.endpoint.trim_end_matches('/'), self.bucket));
+
+        format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            self.object_key(cid)
+        )
+    }
+
+    async fn upload(
+        &self,
+        client: &TrustblockClient,
+        report_file_path: &Path,
+    ) -> eyre::Result<(String, String)> {
+        let cid = file_cid(report_file_path)?;
+
+        let body = std::fs::read(report_file_path)?;
+
+        let canonical_uri = format!("/{}/{}", self.bucket, self.object_key(&cid))
+            .split('/')
+            .map(uri_encode)
+            .collect::<Vec<String>>()
+            .join("/");
+
+        let url = Url::parse(&format!(
+            "{}{canonical_uri}",
+            self.endpoint.trim_end_matches('/')
+        ))?;
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().wrap_err("Invalid S3 endpoint")?),
+            None => url.host_str().wrap_err("Invalid S3 endpoint")?.to_string(),
+        };
+
+        let payload_hash = format!("{:x}", Sha256::digest(&body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let signer = Self {
+            secret_access_key: vault::reveal(&self.secret_access_key)?,
+            ..self.clone()
+        };
+
+        let authorization = signer.authorization(
+            "PUT",
+            url.path(),
+            &[
+                ("content-type", "application/pdf"),
+                ("host", &host),
+                ("x-amz-content-sha256", &payload_hash),
+                ("x-amz-date", &amz_date),
+            ],
+            &payload_hash,
+            &amz_date,
+        );
+
+        sh_println!("[+] Uploading report to S3 bucket {}", self.bucket);
+
+        let response = client
+            .settings()
+            .retry
+            .send(true, || {
+                client
+                    .transfer_http()
+                    .put(url.clone())
+                    .header(CONTENT_TYPE, "application/pdf")
+                    .header("x-amz-content-sha256", &payload_hash)
+                    .header("x-amz-date", &amz_date)
+                    .header("authorization", &authorization)
+                    .body(body.clone())
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(eyre::Report::new(TrustblockError::StorageRejected {
+                store: self.name(),
+                status: response.status(),
+                message: response.text().await.unwrap_or_default(),
+            })
+            .wrap_err("Could not upload report to S3"));
+        }
+
+        sh_println!("[+] Uploading is done\n");
+
+        Ok((cid.clone(), self.report_url(&cid)))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes a path segment as required by Signature Version 4.
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(byte).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use eyre::ContextCompat;
+use reqwest::header::ACCEPT;
+use serde_json::Value;
+
+use super::{ReportStore, StoreSettings};
+use crate::{
+    client::TrustblockClient, constants::WEB3_STORAGE_ENDPOINT, error::TrustblockError, sh_println,
+};
+
+/// Uploads to web3.storage with a short lived token issued by Trustblock.
+///
+/// The report is sent with the HTTP client of the profile, so the proxy, CA
+/// bundle and timeout settings apply.
+#[derive(Debug, Clone)]
+pub struct Web3StorageStore {
+    pub api_url: String,
+}
+
+impl Web3StorageStore {
+    /// Reads `web3_storage_url`.
+    #[must_use]
+    pub fn from_settings(settings: &StoreSettings) -> Self {
+        Self {
+            api_url: settings.web3_storage_url.clone(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl ReportStore for Web3StorageStore {
+    fn name(&self) -> &'static str {
+        "web3storage"
+    }
+
+    fn report_url(&self, cid: &str) -> String {
+        format!("https://{cid}{WEB3_STORAGE_ENDPOINT}")
+    }
+
+    async fn upload(
+        &self,
+        client: &TrustblockClient,
+        report_file_path: &Path,
+    ) -> eyre::Result<(String, String)> {
+        let file_name = report_file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .wrap_err("Invalid File Path")?
+            .to_string();
+
+        let token = client.request_upload_token().await?;
+
+        sh_println!("[+] Uploading {file_name} to web3.storage");
+
+        let body = std::fs::read(report_file_path)?;
+
+        // Uploads are content addressed, so a failed upload is safe to repeat
+        let response = client
+            .settings()
+            .retry
+            .send(true, || {
+                client
+                    .transfer_http()
+                    .post(format!("{}/upload", self.api_url.trim_end_matches('/')))
+                    .bearer_auth(&token)
+                    .header("X-Name", &file_name)
+                    .header(ACCEPT, "application/json")
+                    .body(body.clone())
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(eyre::Report::new(TrustblockError::StorageRejected {
+                store: self.name(),
+                status: response.status(),
+                message: response.text().await.unwrap_or_default(),
+            })
+            .wrap_err("Could not upload the report to web3.storage"));
+        }
+
+        let cid = response.json::<Value>().await?["cid"]
+            .as_str()
+            .wrap_err("web3.storage did not return the report CID")?
+            .to_string();
+
+        sh_println!("[+] Uploading is done\n");
+
+        Ok((cid.clone(), self.report_url(&cid)))
+    }
+}
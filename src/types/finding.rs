@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::types::{Severity, Status};
+
+/// A finding of the audit report, the issue counts are derived from.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    pub severity: Severity,
+    pub status: Status,
+    /// Markdown description of the issue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[validate]
+    pub affected: Vec<AffectedCode>,
+    /// Markdown description of the fix, or why the risk is accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+/// Code a finding affects: a contract, by name or address, and optionally
+/// the lines of its source.
+#[derive(Debug, Clone, Validate, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_lines", skip_on_field_errors = false))]
+pub struct AffectedCode {
+    #[validate(length(min = 1))]
+    pub contract: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub start_line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub end_line: Option<u32>,
+}
+
+fn validate_lines(affected: &AffectedCode) -> Result<(), ValidationError> {
+    match (affected.start_line, affected.end_line) {
+        (Some(start_line), Some(end_line)) if end_line < start_line => {
+            let mut error = ValidationError::new("lines");
+            error.message = Some("endLine must not be before startLine".into());
+            Err(error)
+        }
+        (None, Some(_)) => {
+            let mut error = ValidationError::new("lines");
+            error.message = Some("endLine requires startLine".into());
+            Err(error)
+        }
+        _ => Ok(()),
+    }
+}
@@ -1,16 +1,18 @@
+mod finding;
 mod project;
 
 use std::convert::From;
 
+#[cfg(feature = "cli")]
 use clap::ValueEnum;
 use ethers_core::types::Address;
+pub use finding::{AffectedCode, Finding};
 pub use project::Project;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use strum::{EnumIter, EnumString};
 use validator::Validate;
 
-use crate::cmd::serialize_issues;
-
 #[derive(
     Clone,
     Copy,
@@ -36,7 +38,8 @@ pub enum Chains {
     Avalanche,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, ValueEnum, Hash, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Hash, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Tag {
     Token,
@@ -48,54 +51,107 @@ pub enum Tag {
     Other,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, ValueEnum, Hash, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Hash, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum Status {
     #[serde(rename = "FIXED")]
     Fixed,
     #[serde(rename = "RISK_ACCEPTED")]
     RiskAccepted,
+    #[serde(rename = "ACKNOWLEDGED")]
+    Acknowledged,
+    #[serde(rename = "PARTIALLY_FIXED")]
+    PartiallyFixed,
+    #[serde(rename = "MITIGATED")]
+    Mitigated,
+    #[serde(rename = "UNRESOLVED")]
+    Unresolved,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, ValueEnum, Hash, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Hash, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Severity {
+    Gas,
+    Informational,
     Low,
     Medium,
     High,
     Critical,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Default, Serialize, Validate)]
-#[serde(rename_all = "UPPERCASE")]
+// Missing severities count 0, so older audit.json files without the newer
+// ones load. Counts are bounded by `validate_audit`, with the limit of the
+// profile.
+#[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Default, Serialize)]
+#[serde(default)]
 pub struct SeverityCount {
-    #[validate(range(max = 50))]
-    pub low: u8,
-    #[validate(range(max = 50))]
-    pub medium: u8,
-    #[validate(range(max = 50))]
-    pub high: u8,
-    #[validate(range(max = 50))]
-    pub critical: u8,
+    #[serde(rename = "GAS")]
+    pub gas: u32,
+    #[serde(rename = "INFORMATIONAL")]
+    pub informational: u32,
+    #[serde(rename = "LOW")]
+    pub low: u32,
+    #[serde(rename = "MEDIUM")]
+    pub medium: u32,
+    #[serde(rename = "HIGH")]
+    pub high: u32,
+    #[serde(rename = "CRITICAL")]
+    pub critical: u32,
 }
 
 impl SeverityCount {
     #[must_use]
-    pub const fn new(low: u8, medium: u8, high: u8, critical: u8) -> Self {
+    pub const fn new(low: u32, medium: u32, high: u32, critical: u32) -> Self {
         Self {
+            gas: 0,
+            informational: 0,
             low,
             medium,
             high,
             critical,
         }
     }
+
+    /// Count of each severity, from the lowest to the highest.
+    #[must_use]
+    pub const fn counts(&self) -> [(Severity, u32); 6] {
+        [
+            (Severity::Gas, self.gas),
+            (Severity::Informational, self.informational),
+            (Severity::Low, self.low),
+            (Severity::Medium, self.medium),
+            (Severity::High, self.high),
+            (Severity::Critical, self.critical),
+        ]
+    }
+
+    pub fn count_mut(&mut self, severity: Severity) -> &mut u32 {
+        match severity {
+            Severity::Gas => &mut self.gas,
+            Severity::Informational => &mut self.informational,
+            Severity::Low => &mut self.low,
+            Severity::Medium => &mut self.medium,
+            Severity::High => &mut self.high,
+            Severity::Critical => &mut self.critical,
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Default, Serialize)]
 pub struct IssueCount {
     #[serde(rename = "FIXED")]
     pub fixed: SeverityCount,
     #[serde(rename = "RISK_ACCEPTED")]
     pub risk_accepted: SeverityCount,
+    #[serde(rename = "ACKNOWLEDGED", default)]
+    pub acknowledged: SeverityCount,
+    #[serde(rename = "PARTIALLY_FIXED", default)]
+    pub partially_fixed: SeverityCount,
+    #[serde(rename = "MITIGATED", default)]
+    pub mitigated: SeverityCount,
+    #[serde(rename = "UNRESOLVED", default)]
+    pub unresolved: SeverityCount,
 }
 
 impl IssueCount {
@@ -104,8 +160,92 @@ impl IssueCount {
         Self {
             fixed,
             risk_accepted,
+            acknowledged: SeverityCount::new(0, 0, 0, 0),
+            partially_fixed: SeverityCount::new(0, 0, 0, 0),
+            mitigated: SeverityCount::new(0, 0, 0, 0),
+            unresolved: SeverityCount::new(0, 0, 0, 0),
+        }
+    }
+
+    /// Severity counts of each status.
+    #[must_use]
+    pub const fn counts(&self) -> [(Status, SeverityCount); 6] {
+        [
+            (Status::Fixed, self.fixed),
+            (Status::RiskAccepted, self.risk_accepted),
+            (Status::Acknowledged, self.acknowledged),
+            (Status::PartiallyFixed, self.partially_fixed),
+            (Status::Mitigated, self.mitigated),
+            (Status::Unresolved, self.unresolved),
+        ]
+    }
+
+    pub fn severity_count_mut(&mut self, status: Status) -> &mut SeverityCount {
+        match status {
+            Status::Fixed => &mut self.fixed,
+            Status::RiskAccepted => &mut self.risk_accepted,
+            Status::Acknowledged => &mut self.acknowledged,
+            Status::PartiallyFixed => &mut self.partially_fixed,
+            Status::Mitigated => &mut self.mitigated,
+            Status::Unresolved => &mut self.unresolved,
         }
     }
+
+    /// Counts the findings per status and severity.
+    #[must_use]
+    pub fn from_findings(findings: &[Finding]) -> Self {
+        Self::from_tallies(findings.iter().map(|finding| IssueTally {
+            status: finding.status,
+            severity: finding.severity,
+            count: 1,
+        }))
+    }
+
+    /// Counts issues expanded with [`get_transformed_issues`].
+    #[must_use]
+    pub fn from_issues(issues: &[Issue]) -> Self {
+        Self::from_tallies(issues.iter().map(|issue| IssueTally {
+            status: issue.status,
+            severity: issue.severity,
+            count: 1,
+        }))
+    }
+
+    /// Sums tallies, like the ones of [`get_issue_tallies`], per status and
+    /// severity.
+    #[must_use]
+    pub fn from_tallies(tallies: impl IntoIterator<Item = IssueTally>) -> Self {
+        let mut issues = Self::default();
+
+        for tally in tallies {
+            let count = issues
+                .severity_count_mut(tally.status)
+                .count_mut(tally.severity);
+
+            *count = count.saturating_add(tally.count);
+        }
+
+        issues
+    }
+}
+
+/// Number of issues with a status and a severity, the wire form of
+/// [`IssueCount`].
+///
+/// Entries without `count`, like the ones of [`get_transformed_issues`], are a
+/// single issue.
+#[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Serialize)]
+pub struct IssueTally {
+    pub status: Status,
+    pub severity: Severity,
+    #[serde(default = "IssueTally::single")]
+    pub count: u32,
+}
+
+impl IssueTally {
+    const fn single() -> u32 {
+        1
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Hash, Eq, PartialEq, Serialize)]
@@ -128,23 +268,87 @@ pub struct Contact {
 }
 
 #[derive(Debug, Clone, Serialize, Validate, Deserialize)]
+#[serde(try_from = "AuditFile")]
 pub struct Audit {
-    #[serde(skip_deserializing)]
     pub chains: Vec<Chains>,
     #[serde(serialize_with = "serialize_issues")]
     pub issues: IssueCount,
+    /// Findings of the report, `issues` is derived from them when they are
+    /// given.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[validate]
+    pub findings: Vec<Finding>,
     pub tags: Vec<Tag>,
     pub contracts: Vec<AuditContract>,
     pub description: Description,
     pub name: String,
-    #[serde(rename = "reportHash", skip_deserializing)]
+    #[serde(rename = "reportHash")]
     pub report_hash: String,
     #[validate(url)]
-    #[serde(rename = "reportFileUrl", skip_deserializing)]
+    #[serde(rename = "reportFileUrl")]
     pub report_file_url: String,
+    #[validate]
     pub project: Project,
 }
 
+/// `audit.json` as written by auditors: the issue counts, the findings they
+/// are derived from, or both when they agree.
+#[derive(Deserialize)]
+struct AuditFile {
+    #[serde(default, deserialize_with = "deserialize_optional_issues")]
+    issues: Option<IssueCount>,
+    #[serde(default)]
+    findings: Vec<Finding>,
+    tags: Vec<Tag>,
+    contracts: Vec<AuditContract>,
+    description: Description,
+    name: String,
+    project: Project,
+    // Filled in while publishing, read back from payloads
+    #[serde(default)]
+    chains: Vec<Chains>,
+    #[serde(rename = "reportHash", default)]
+    report_hash: String,
+    #[serde(rename = "reportFileUrl", default)]
+    report_file_url: String,
+}
+
+impl TryFrom<AuditFile> for Audit {
+    type Error = String;
+
+    fn try_from(file: AuditFile) -> Result<Self, Self::Error> {
+        let issues = match (file.issues, file.findings.is_empty()) {
+            (Some(issues), true) => issues,
+            (None, true) => return Err("missing field `issues` or `findings`".to_string()),
+            (issues, false) => {
+                let derived = IssueCount::from_findings(&file.findings);
+
+                if issues.is_some_and(|issues| issues != derived) {
+                    return Err(format!(
+                        "`issues` do not match the {} findings, remove `issues` to derive them from `findings`",
+                        file.findings.len()
+                    ));
+                }
+
+                derived
+            }
+        };
+
+        Ok(Self {
+            chains: file.chains,
+            issues,
+            findings: file.findings,
+            tags: file.tags,
+            contracts: file.contracts,
+            description: file.description,
+            name: file.name,
+            report_hash: file.report_hash,
+            report_file_url: file.report_file_url,
+            project: file.project,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AuditContract {
     pub chain: Chains,
@@ -175,3 +379,72 @@ pub struct Links {
     #[validate(url)]
     pub website: Option<String>,
 }
+
+/// Serializes the counts as [`IssueTally`] entries, leaving out empty ones,
+/// so large audits are not sent as thousands of identical issues.
+pub fn serialize_issues<S>(issues: &IssueCount, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    get_issue_tallies(issues).serialize(serializer)
+}
+
+#[must_use]
+pub fn get_issue_tallies(issues: &IssueCount) -> Vec<IssueTally> {
+    issues
+        .counts()
+        .into_iter()
+        .flat_map(|(status, severity_count)| {
+            severity_count
+                .counts()
+                .into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(move |(severity, count)| IssueTally {
+                    status,
+                    severity,
+                    count,
+                })
+        })
+        .collect()
+}
+
+/// Reads issues serialized with [`serialize_issues`], in the `audit.json` form
+/// of [`IssueCount`], or expanded by [`get_transformed_issues`].
+pub fn deserialize_issues<'de, D>(deserializer: D) -> Result<IssueCount, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Dispatched on the shape rather than with an untagged enum, to keep the
+    // errors of the expected form
+    let value = Value::deserialize(deserializer)?;
+
+    if value.is_array() {
+        serde_json::from_value::<Vec<IssueTally>>(value).map(IssueCount::from_tallies)
+    } else {
+        serde_json::from_value::<IssueCount>(value)
+    }
+    .map_err(D::Error::custom)
+}
+
+fn deserialize_optional_issues<'de, D>(deserializer: D) -> Result<Option<IssueCount>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_issues(deserializer).map(Some)
+}
+
+/// Expands the counts into one [`Issue`] per issue.
+#[must_use]
+pub fn get_transformed_issues(issues: &IssueCount) -> Vec<Issue> {
+    let mut result: Vec<Issue> = Vec::new();
+
+    for (status, severity_count) in issues.counts() {
+        for (severity, count) in severity_count.counts() {
+            for _ in 0..count {
+                result.push(Issue { status, severity });
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use eyre::{eyre, ContextCompat};
+use flate2::read::GzDecoder;
+use reqwest::{header, Client, Response, StatusCode};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::Settings,
+    constants::{CLI_PATH, CRATES_API_RELEASE_ENDPOINT},
+    http::HttpSettings,
+    retry::RetryPolicy,
+};
+
+/// Version of this build.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How long the result of a check is reused before checking again.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum time of the request to crates.io.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Target triple of this build, release archives are picked by it.
+pub const TARGET: &str = env!("TARGET");
+
+/// Name of the binary inside release archives.
+pub const BINARY_NAME: &str = if cfg!(windows) {
+    "trustblock.exe"
+} else {
+    "trustblock"
+};
+
+/// Manifest cargo-dist publishes with each release, listing its archives.
+const DIST_MANIFEST: &str = "dist-manifest.json";
+
+/// Disables the update check when set to any value.
+pub const NO_UPDATE_CHECK_VAR: &str = "TRUSTBLOCK_NO_UPDATE_CHECK";
+
+const USER_AGENT: &str = concat!(
+    "trustblock-cli/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Trustblock-Inc/trustblock-cli)"
+);
+
+/// Result of the last update check, cached in
+/// `~/.trustblock/update-check.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCache {
+    pub checked_at: DateTime<Utc>,
+    /// `None` when the check failed, so offline runs do not retry it on every
+    /// command.
+    pub latest_version: Option<String>,
+}
+
+impl UpdateCache {
+    pub fn path() -> eyre::Result<PathBuf> {
+        let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
+
+        Ok(home_dir.join(CLI_PATH).join("update-check.json"))
+    }
+
+    /// Reads the cache, `None` if it is missing or invalid.
+    #[must_use]
+    pub fn load() -> Option<Self> {
+        serde_json::from_str(&std::fs::read_to_string(Self::path().ok()?).ok()?).ok()
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        let path = Self::path()?;
+
+        if let Some(cache_dir) = path.parent() {
+            std::fs::create_dir_all(cache_dir)?;
+        }
+
+        Ok(std::fs::write(path, serde_json::to_string(self)?)?)
+    }
+
+    /// Whether the check was done less than [`CHECK_INTERVAL`] before `now`.
+    #[must_use]
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(self.checked_at)
+            .to_std()
+            .is_ok_and(|age| age < CHECK_INTERVAL)
+    }
+}
+
+/// Whether the update check is disabled by [`NO_UPDATE_CHECK_VAR`] or the
+/// `update_check` setting.
+#[must_use]
+pub fn is_disabled(settings: &Settings) -> bool {
+    std::env::var_os(NO_UPDATE_CHECK_VAR).is_some_and(|value| !value.is_empty())
+        || !settings.update_check
+}
+
+/// Fetches the latest stable version published on crates.io.
+pub async fn fetch_latest_version(client: &Client, endpoint: &str) -> eyre::Result<Version> {
+    let response = client
+        .get(endpoint)
+        .header(header::USER_AGENT, USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let body = response.json::<Value>().await?;
+
+    let version = body["crate"]["max_stable_version"]
+        .as_str()
+        .or_else(|| body["crate"]["newest_version"].as_str())
+        .wrap_err("The crates.io response has no version")?;
+
+    Ok(Version::parse(version)?)
+}
+
+/// Returns `latest` if it is a valid version newer than `current`.
+#[must_use]
+pub fn newer_version(latest: &str, current: &str) -> Option<Version> {
+    let latest = Version::parse(latest).ok()?;
+
+    (latest > Version::parse(current).ok()?).then_some(latest)
+}
+
+/// Returns the version to update to, if any.
+///
+/// The latest version is read from the cache while it is fresh, otherwise it
+/// is fetched within [`CHECK_TIMEOUT`] and cached, failures included.
+pub async fn check(settings: &Settings) -> Option<Version> {
+    let now = Utc::now();
+
+    let cache = match UpdateCache::load() {
+        Some(cache) if cache.is_fresh(now) => cache,
+        _ => {
+            let http = HttpSettings {
+                connect_timeout: settings.http.connect_timeout.min(CHECK_TIMEOUT),
+                timeout: settings.http.timeout.min(CHECK_TIMEOUT),
+                ..settings.http.clone()
+            };
+
+            let latest_version = match http.client() {
+                Ok(client) => fetch_latest_version(&client, CRATES_API_RELEASE_ENDPOINT)
+                    .await
+                    .ok(),
+                Err(_) => None,
+            };
+
+            let cache = UpdateCache {
+                checked_at: now,
+                latest_version: latest_version.map(|version| version.to_string()),
+            };
+
+            let _ = cache.save();
+
+            cache
+        }
+    };
+
+    newer_version(cache.latest_version.as_deref()?, CURRENT_VERSION)
+}
+
+/// Releases listed in a [`DIST_MANIFEST`].
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct DistManifest {
+    #[serde(default)]
+    pub releases: Vec<DistRelease>,
+    #[serde(default)]
+    pub artifacts: BTreeMap<String, DistArtifact>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DistRelease {
+    pub app_name: String,
+    pub app_version: String,
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DistArtifact {
+    pub kind: String,
+    #[serde(default)]
+    pub target_triples: Vec<String>,
+}
+
+/// Archive of a release for [`TARGET`], with its published checksum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub version: Version,
+    pub archive_url: String,
+    pub checksum_url: String,
+}
+
+/// Where releases are downloaded from, laid out like GitHub releases: the
+/// files of a release under `<release_url>/download/v<version>/`, and the
+/// latest [`DIST_MANIFEST`] at `<release_url>/latest/download/`.
+#[derive(Clone, Debug)]
+pub struct ReleaseSource {
+    http: Client,
+    transfer: Client,
+    retry: RetryPolicy,
+    url: String,
+}
+
+impl ReleaseSource {
+    /// Builds the source of the `release_url` setting.
+    pub fn new(settings: &Settings) -> eyre::Result<Self> {
+        Ok(Self {
+            http: settings.http.client()?,
+            transfer: settings.http.transfer_client()?,
+            retry: settings.retry,
+            url: settings.release_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Finds the archive of a release for [`TARGET`], the latest one if no
+    /// version is given.
+    pub async fn find(&self, version: Option<&Version>) -> eyre::Result<Release> {
+        let manifest_url = match version {
+            Some(version) => format!("{}/download/v{version}/{DIST_MANIFEST}", self.url),
+            None => format!("{}/latest/download/{DIST_MANIFEST}", self.url),
+        };
+
+        let response = self
+            .retry
+            .send(true, || self.http.get(&manifest_url))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(eyre!("No release found at {manifest_url}"));
+        }
+
+        let manifest = response.error_for_status()?.json::<DistManifest>().await?;
+
+        let release = manifest
+            .releases
+            .iter()
+            .find(|release| release.app_name == env!("CARGO_PKG_NAME"))
+            .wrap_err_with(|| {
+                format!("{manifest_url} has no {} release", env!("CARGO_PKG_NAME"))
+            })?;
+
+        let release_version = Version::parse(&release.app_version)?;
+
+        let archive = release
+            .artifacts
+            .iter()
+            .find(|name| {
+                manifest.artifacts.get(*name).is_some_and(|artifact| {
+                    artifact.kind == "executable-zip"
+                        && artifact
+                            .target_triples
+                            .iter()
+                            .any(|target| target == TARGET)
+                })
+            })
+            .wrap_err_with(|| format!("Release {release_version} has no archive for {TARGET}"))?;
+
+        let archive_url = format!("{}/download/v{release_version}/{archive}", self.url);
+
+        Ok(Release {
+            version: release_version,
+            checksum_url: format!("{archive_url}.sha256"),
+            archive_url,
+        })
+    }
+
+    /// Downloads the archive of a release and checks it against its published
+    /// SHA-256 checksum.
+    pub async fn download(&self, release: &Release) -> eyre::Result<Vec<u8>> {
+        let archive = self
+            .retry
+            .send(true, || self.transfer.get(&release.archive_url))
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let checksum_file = self.get(&release.checksum_url).await?.text().await?;
+
+        // Checksum files hold the hash, optionally followed by the file name
+        let expected = checksum_file
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let actual = format!("{:x}", Sha256::digest(&archive));
+
+        if expected != actual {
+            return Err(eyre!(
+                "Checksum mismatch for {}: expected {expected}, got {actual}",
+                release.archive_url
+            ));
+        }
+
+        Ok(archive.to_vec())
+    }
+
+    async fn get(&self, url: &str) -> eyre::Result<Response> {
+        let response = self.retry.send(true, || self.http.get(url)).await?;
+
+        Ok(response.error_for_status()?)
+    }
+}
+
+/// Extracts [`BINARY_NAME`] from a `.tar.gz` release archive.
+pub fn extract_binary(archive: &[u8]) -> eyre::Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if entry.path()?.file_name() == Some(OsStr::new(BINARY_NAME)) {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary)?;
+
+            return Ok(binary);
+        }
+    }
+
+    Err(eyre!("The release archive has no {BINARY_NAME} binary"))
+}
+
+/// Replaces the binary at `path` with a new one, and restores the previous
+/// binary if the new one does not report the expected version.
+pub fn replace_binary(path: &Path, binary: &[u8], version: &Version) -> eyre::Result<()> {
+    let dir = path
+        .parent()
+        .wrap_err_with(|| format!("{path:?} has no parent directory"))?;
+    let file_name = path
+        .file_name()
+        .wrap_err_with(|| format!("{path:?} has no file name"))?
+        .to_string_lossy();
+
+    // Written next to the binary so renaming it is atomic
+    let new_path = dir.join(format!(".{file_name}.new"));
+    let backup_path = dir.join(format!(".{file_name}.old"));
+
+    std::fs::write(&new_path, binary).map_err(|error| {
+        eyre::Report::new(error).wrap_err(format!(
+            "Could not write to {dir:?}. Run the command as a user that can write there"
+        ))
+    })?;
+    std::fs::set_permissions(&new_path, std::fs::metadata(path)?.permissions())?;
+
+    let _ = std::fs::remove_file(&backup_path);
+
+    // The running binary can not be overwritten on Windows, only renamed
+    if cfg!(windows) {
+        std::fs::rename(path, &backup_path)?;
+    } else {
+        std::fs::hard_link(path, &backup_path)
+            .or_else(|_| std::fs::copy(path, &backup_path).map(drop))?;
+    }
+
+    let installed = std::fs::rename(&new_path, path)
+        .map_err(eyre::Report::new)
+        .and_then(|()| verify_binary(path, version));
+
+    if let Err(error) = installed {
+        std::fs::rename(&backup_path, path)?;
+        let _ = std::fs::remove_file(&new_path);
+
+        return Err(error.wrap_err(format!("Could not install {version}, restored {path:?}")));
+    }
+
+    // Fails on Windows while the previous binary runs, it is replaced by the
+    // next update
+    let _ = std::fs::remove_file(&backup_path);
+
+    Ok(())
+}
+
+/// Checks that a binary runs and reports the given version.
+fn verify_binary(path: &Path, version: &Version) -> eyre::Result<()> {
+    let output = Command::new(path).arg("--version").output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if !output.status.success() || !stdout.contains(&version.to_string()) {
+        return Err(eyre!(
+            "The new binary reported {:?} instead of version {version}",
+            stdout.trim()
+        ));
+    }
+
+    Ok(())
+}
@@ -1,8 +1,13 @@
-use std::{fs::File, io::BufReader, path::PathBuf};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
 use eyre::{eyre, ContextCompat};
 use pdf::file::FileOptions as PdfFile;
 use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
 use validator::{validate_email, validate_url};
 
 use crate::constants::CLI_PATH;
@@ -28,6 +33,16 @@ pub fn parse_json<T: DeserializeOwned>(path: &PathBuf) -> eyre::Result<T> {
     Ok(data)
 }
 
+/// Returns the hex encoded SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> eyre::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 pub fn validate_links(link: &str) -> eyre::Result<String> {
     if !validate_url(link) {
         return Err(eyre!("Invalid URL"));
@@ -62,10 +77,15 @@ pub fn validate_pdf(val: &str) -> eyre::Result<PathBuf> {
     Ok(path_buf)
 }
 
+/// Loads `~/.trustblock/.env` into the environment, if it exists.
 pub fn apply_dotenv() -> eyre::Result<()> {
     let home_dir = dirs::home_dir().wrap_err("Could not find home directory")?;
 
     let env_path = home_dir.join(format!("{CLI_PATH}/.env"));
 
+    if !env_path.exists() {
+        return Ok(());
+    }
+
     Ok(dotenv::from_path(env_path.as_path())?)
 }
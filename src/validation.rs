@@ -0,0 +1,142 @@
+use std::fmt;
+
+use serde::Serialize;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::{error::TrustblockError, types::Audit};
+
+/// Fields filled in by the CLI while publishing, which are empty in
+/// `audit.json`.
+const GENERATED_FIELDS: [&str; 1] = ["reportFileUrl"];
+
+/// Issues of a status and severity accepted when the profile sets no
+/// `max_issues_per_severity`.
+pub const DEFAULT_MAX_ISSUES_PER_SEVERITY: u32 = 1000;
+
+/// A single validation failure with the JSON path of the offending field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Runs the `validator` rules on the audit and its nested structs, and checks
+/// that no status and severity has more than `max_issues_per_severity` issues,
+/// collecting every violation.
+///
+/// Fields that are generated during publishing are skipped while they are still
+/// empty.
+pub fn validate_audit(audit: &Audit, max_issues_per_severity: u32) -> Result<(), Vec<FieldError>> {
+    let mut field_errors = Vec::new();
+
+    if let Err(errors) = audit.validate() {
+        collect_errors(&errors, "", &mut field_errors);
+    }
+
+    for (status, severity_count) in audit.issues.counts() {
+        for (severity, count) in severity_count.counts() {
+            if count > max_issues_per_severity {
+                field_errors.push(FieldError {
+                    path: format!("issues.{}.{}", json_key(&status), json_key(&severity)),
+                    message: format!("must be at most {max_issues_per_severity}"),
+                });
+            }
+        }
+    }
+
+    field_errors.retain(|error| {
+        !(GENERATED_FIELDS.contains(&error.path.as_str()) && audit.report_file_url.is_empty())
+    });
+
+    if field_errors.is_empty() {
+        return Ok(());
+    }
+
+    field_errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Err(field_errors)
+}
+
+/// Validates the audit and turns the violations into a single error listing
+/// all of them.
+pub fn ensure_valid_audit(
+    audit: &Audit,
+    max_issues_per_severity: u32,
+) -> Result<(), TrustblockError> {
+    validate_audit(audit, max_issues_per_severity).map_err(|fields| TrustblockError::Validation {
+        message: "Audit data is invalid".to_string(),
+        fields,
+    })
+}
+
+fn collect_errors(errors: &ValidationErrors, prefix: &str, result: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        // Struct level checks are reported on the struct itself
+        let path = match (prefix.is_empty(), *field) {
+            (false, "__all__") => prefix.to_string(),
+            (true, _) => (*field).to_string(),
+            (false, _) => format!("{prefix}.{field}"),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                result.extend(field_errors.iter().map(|error| FieldError {
+                    path: path.clone(),
+                    message: describe(error),
+                }));
+            }
+            ValidationErrorsKind::Struct(errors) => collect_errors(errors, &path, result),
+            ValidationErrorsKind::List(errors) => {
+                for (index, errors) in errors {
+                    collect_errors(errors, &format!("{path}[{index}]"), result);
+                }
+            }
+        }
+    }
+}
+
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+
+    // Range bounds are stored as floats, print whole numbers without the fraction
+    let param = |name: &str| {
+        error.params.get(name).map(|value| match value.as_f64() {
+            Some(number) if number.fract() == 0.0 => format!("{number:.0}"),
+            _ => value.to_string(),
+        })
+    };
+
+    match error.code.as_ref() {
+        "url" => "invalid url".to_string(),
+        "email" => "invalid email".to_string(),
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("length must be between {min} and {max}"),
+            (Some(min), None) => format!("length must be at least {min}"),
+            (None, Some(max)) => format!("length must be at most {max}"),
+            (None, None) => "invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {min} and {max}"),
+            (Some(min), None) => format!("must be at least {min}"),
+            (None, Some(max)) => format!("must be at most {max}"),
+            (None, None) => "out of range".to_string(),
+        },
+        code => format!("failed `{code}` check"),
+    }
+}
+
+/// Name of a unit enum variant in JSON.
+fn json_key(value: &impl Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
@@ -0,0 +1,167 @@
+use std::{
+    env::VarError,
+    io::{IsTerminal, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use eyre::eyre;
+
+use crate::error::TrustblockError;
+
+/// Prefix of secrets encrypted with [`encrypt`].
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+const SALT_LENGTH: usize = 16;
+
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+#[must_use]
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts a secret with a key derived from `passphrase` with Argon2id, and
+/// XChaCha20-Poly1305.
+///
+/// The result is `enc:v1:<salt>:<nonce>:<ciphertext>`, base64 encoded.
+pub fn encrypt(secret: &str, passphrase: &str) -> eyre::Result<String> {
+    let mut salt = [0; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = cipher(passphrase, &salt)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, secret.as_bytes())
+        .map_err(|_| eyre!("Could not encrypt secret"))?;
+
+    Ok(format!(
+        "{ENCRYPTED_PREFIX}{}:{}:{}",
+        STANDARD.encode(salt),
+        STANDARD.encode(nonce),
+        STANDARD.encode(ciphertext)
+    ))
+}
+
+/// Decrypts a secret encrypted with [`encrypt`].
+pub fn decrypt(value: &str, passphrase: &str) -> eyre::Result<String> {
+    let parts = value
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .map(|value| value.split(':').collect::<Vec<&str>>())
+        .unwrap_or_default();
+
+    let [salt, nonce, ciphertext] = parts[..] else {
+        return Err(eyre!("Invalid encrypted secret"));
+    };
+
+    let salt = STANDARD.decode(salt)?;
+    let nonce = STANDARD.decode(nonce)?;
+    let ciphertext = STANDARD.decode(ciphertext)?;
+
+    if nonce.len() != 24 {
+        return Err(eyre!("Invalid encrypted secret"));
+    }
+
+    let secret = cipher(passphrase, &salt)?
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| TrustblockError::WrongPassphrase)?;
+
+    Ok(String::from_utf8(secret)?)
+}
+
+/// Decrypts a secret if it is encrypted, asking for the passphrase once per
+/// run.
+pub fn reveal(value: &str) -> eyre::Result<String> {
+    if !is_encrypted(value) {
+        return Ok(value.to_string());
+    }
+
+    let mut cached = PASSPHRASE.lock().expect("passphrase lock poisoned");
+
+    let passphrase = match cached.clone() {
+        Some(passphrase) => passphrase,
+        None => passphrase("Passphrase of the encrypted API key: ")?,
+    };
+
+    let secret = decrypt(value, &passphrase)?;
+
+    *cached = Some(passphrase);
+
+    Ok(secret)
+}
+
+/// Reads the passphrase from `TRUSTBLOCK_PASSPHRASE`, or prompts for it on
+/// a terminal.
+pub fn passphrase(prompt: &str) -> eyre::Result<String> {
+    if let Ok(passphrase) = std::env::var("TRUSTBLOCK_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        return Err(eyre::Report::new(VarError::NotPresent)
+            .wrap_err("Set TRUSTBLOCK_PASSPHRASE to use an encrypted API key without a terminal"));
+    }
+
+    Ok(rpassword::prompt_password(prompt)?)
+}
+
+/// Asks for a new passphrase, twice when prompting.
+pub fn new_passphrase() -> eyre::Result<String> {
+    if let Ok(passphrase) = std::env::var("TRUSTBLOCK_PASSPHRASE") {
+        if passphrase.is_empty() {
+            return Err(eyre!("TRUSTBLOCK_PASSPHRASE can not be empty"));
+        }
+
+        return Ok(passphrase);
+    }
+
+    let passphrase = passphrase("New passphrase for the API key: ")?;
+
+    if passphrase.is_empty() {
+        return Err(eyre!("Passphrase can not be empty"));
+    }
+
+    if passphrase != rpassword::prompt_password("Repeat the passphrase: ")? {
+        return Err(eyre!("Passphrases do not match"));
+    }
+
+    Ok(passphrase)
+}
+
+/// Writes a file only readable by its owner.
+pub fn write_private(path: &Path, contents: &str) -> eyre::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    options.open(path)?.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> eyre::Result<XChaCha20Poly1305> {
+    let mut key = [0; 32];
+
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| eyre!("Could not derive key from passphrase: {error}"))?;
+
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
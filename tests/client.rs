@@ -0,0 +1,222 @@
+use mockito::{Matcher, Server};
+use serde_json::json;
+use trustblock_cli::{
+    client::{ReportSource, TrustblockClient},
+    cmd::block_on,
+    config::{Profile, Settings},
+    constants::TRUSTBLOCK_API_KEY_HEADER,
+    error::TrustblockError,
+    ipfs::file_cid,
+    journal::PublishJournal,
+    types::Audit,
+    utils::parse_json,
+};
+
+const AUDIT_JSON: &str = "tests/test-data/audit.json";
+const REPORT_PDF: &str = "tests/test-data/report.pdf";
+
+fn client(server: &Server) -> eyre::Result<TrustblockClient> {
+    let settings = Settings::resolve(
+        "test".to_string(),
+        Profile {
+            api_url: Some(format!("{}/api", server.url())),
+            report_store: Some("kubo".to_string()),
+            kubo_api_url: Some(server.url()),
+            ..Profile::default()
+        },
+    )?;
+
+    TrustblockClient::new(settings, "api_key")
+}
+
+fn publish(server: &Server) -> eyre::Result<trustblock_cli::client::PublishedAudit> {
+    let audit = parse_json::<Audit>(&AUDIT_JSON.into())?;
+
+    block_on(client(server)?.publish_audit(audit, ReportSource::Pdf(REPORT_PDF.into())))
+}
+
+#[test]
+fn test_publish_audit() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    server
+        .mock("GET", "/api/project/trustblock-run")
+        .match_header(TRUSTBLOCK_API_KEY_HEADER, "api_key")
+        .with_body(json!({ "id": "project_id" }).to_string())
+        .create();
+
+    server
+        .mock("GET", format!("/api/audit/report-hash/{cid}").as_str())
+        .with_status(404)
+        .create();
+
+    let upload = server
+        .mock("POST", "/api/v0/add")
+        .match_query(Matcher::Any)
+        .with_body(json!({ "Hash": cid }).to_string())
+        .create();
+
+    let create = server
+        .mock("POST", "/api/audit/")
+        .match_body(Matcher::PartialJson(
+            json!({ "project": { "id": "project_id" } }),
+        ))
+        .with_status(201)
+        .with_body(json!({ "id": "audit_id" }).to_string())
+        .create();
+
+    let published = publish(&server)?;
+
+    upload.assert();
+    create.assert();
+
+    assert_eq!(published.audit_id.as_deref(), Some("audit_id"));
+    assert_eq!(published.project_id.as_deref(), Some("project_id"));
+    assert_eq!(published.report_cid, cid);
+    assert!(published.warnings.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_publish_audit_resumes_uploaded_report() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let journal_dir = tempfile::tempdir()?;
+    let report = ReportSource::Pdf(REPORT_PDF.into());
+
+    let mut journal =
+        PublishJournal::open_in(journal_dir.path(), AUDIT_JSON.as_ref(), &report, false)?;
+    journal.project_resolved = true;
+    journal.project_id = Some("project_id".to_string());
+    journal.report_cid = Some("bafkreijournal".to_string());
+    journal.report_url = Some("https://bafkreijournal.ipfs.example.com".to_string());
+    journal.save()?;
+
+    let mut journal =
+        PublishJournal::open_in(journal_dir.path(), AUDIT_JSON.as_ref(), &report, true)?;
+
+    let project = server
+        .mock("GET", "/api/project/trustblock-run")
+        .expect(0)
+        .create();
+    let upload = server.mock("POST", "/api/v0/add").expect(0).create();
+    let create = server
+        .mock("POST", "/api/audit/")
+        .with_status(201)
+        .with_body(json!({ "id": "audit_id" }).to_string())
+        .create();
+
+    let audit = parse_json::<Audit>(&AUDIT_JSON.into())?;
+    let published =
+        block_on(client(&server)?.publish_audit_with_journal(audit, report, &mut journal))?;
+
+    project.assert();
+    upload.assert();
+    create.assert();
+
+    assert_eq!(published.report_cid, "bafkreijournal");
+    assert_eq!(published.project_id.as_deref(), Some("project_id"));
+    assert_eq!(
+        std::fs::read_dir(journal_dir.path())?.count(),
+        0,
+        "Journal should be removed once the audit is created"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_publish_audit_duplicate_report() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    server
+        .mock("GET", "/api/project/trustblock-run")
+        .with_status(404)
+        .create();
+
+    server
+        .mock("GET", format!("/api/audit/report-hash/{cid}").as_str())
+        .with_body(json!({ "id": "audit_id" }).to_string())
+        .create();
+
+    let upload = server.mock("POST", "/api/v0/add").expect(0).create();
+
+    let error = publish(&server).expect_err("report is already published");
+
+    upload.assert();
+
+    assert!(matches!(
+        error.downcast_ref::<TrustblockError>(),
+        Some(TrustblockError::DuplicateReport)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_publish_audit_uploads_when_report_check_fails() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    server
+        .mock("GET", "/api/project/trustblock-run")
+        .with_status(404)
+        .create();
+
+    server
+        .mock("GET", format!("/api/audit/report-hash/{cid}").as_str())
+        .with_status(405)
+        .create();
+
+    let upload = server
+        .mock("POST", "/api/v0/add")
+        .match_query(Matcher::Any)
+        .with_body(json!({ "Hash": cid }).to_string())
+        .create();
+
+    server
+        .mock("POST", "/api/audit/")
+        .with_status(201)
+        .with_body(json!({ "id": "audit_id" }).to_string())
+        .create();
+
+    let published = publish(&server)?;
+
+    upload.assert();
+
+    assert_eq!(published.audit_id.as_deref(), Some("audit_id"));
+    assert_eq!(published.warnings.len(), 1);
+    assert!(published.warnings[0]
+        .starts_with("Could not check whether the report is already published"));
+
+    Ok(())
+}
+
+#[test]
+fn test_upload_report_keeps_temporary_files() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    let report_dir = tempfile::tempdir()?;
+    let report_path = report_dir.path().join("report.pdf");
+
+    std::fs::copy(REPORT_PDF, &report_path)?;
+
+    server
+        .mock("POST", "/api/v0/add")
+        .match_query(Matcher::Any)
+        .with_body(json!({ "Hash": cid }).to_string())
+        .create();
+
+    let (report_cid, _) = block_on(client(&server)?.upload_report(&report_path))?;
+
+    assert_eq!(report_cid, cid);
+    assert!(
+        report_path.exists(),
+        "Reports given by the caller should not be removed, even in the temp directory"
+    );
+
+    Ok(())
+}
@@ -4,6 +4,8 @@ pub const FIXTURES_DIR: &str = "tests/test-data/fixtures/";
 
 pub const AUDIT_JSON_PATH: &str = "tests/test-data/audit.json";
 
+pub const AUDIT_FINDINGS_JSON_PATH: &str = "tests/test-data/audit-findings.json";
+
 pub const PDF_REPORTS_PATH: &str = "tests/test-data/pdf_reports";
 
 pub const CLI_PATH: &str = ".trustblock";
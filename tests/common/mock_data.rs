@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use trustblock_cli::types::{AuditContract, Chains, Description, IssueCount, Project, Tag};
+use serde_json::json;
+use trustblock_cli::types::{
+    AuditContract, Chains, Description, IssueCount, IssueTally, Project, Tag,
+};
 use validator::Validate;
 
 #[derive(Debug, Clone, Serialize, Validate, Deserialize)]
@@ -25,3 +28,13 @@ pub struct SQLIssueTempResult {
     pub severity: String,
     pub count: i64,
 }
+
+impl SQLIssueTempResult {
+    pub fn tally(&self) -> eyre::Result<IssueTally> {
+        Ok(serde_json::from_value(json!({
+            "status": self.status,
+            "severity": self.severity,
+            "count": self.count,
+        }))?)
+    }
+}
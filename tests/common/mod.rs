@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 pub mod constants;
 pub mod mock_data;
 pub mod utils;
@@ -55,17 +55,17 @@ pub fn generate_random_audit(project_seed: Option<u64>) -> eyre::Result<(PathBuf
     let random_name: String = repeat_with(fastrand::alphanumeric).take(10).collect();
 
     let severity_count_risk_accepted = SeverityCount::new(
-        fastrand::u8(..5),
-        fastrand::u8(..5),
-        fastrand::u8(..5),
-        fastrand::u8(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
     );
 
     let severity_count_fixed = SeverityCount::new(
-        fastrand::u8(..5),
-        fastrand::u8(..5),
-        fastrand::u8(..5),
-        fastrand::u8(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
+        fastrand::u32(..5),
     );
 
     let issues = IssueCount::new(severity_count_fixed, severity_count_risk_accepted);
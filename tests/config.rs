@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use clap::Parser;
+use serial_test::serial;
+use tempfile::TempDir;
+use trustblock_cli::{
+    cmd::{
+        trustblock::{Cli, Commands},
+        Cmd,
+    },
+    config::{self, ConfigFile, Kind, Layers, Profile, Settings, Source, KEYS},
+    constants::STAGING_API_URL,
+};
+
+const CONFIG: &str = r#"
+default_profile = "staging"
+
+[profiles.staging]
+api_url = "https://staging.example.com/api/"
+api_key = "staging_key"
+pdf_generate_endpoint = "https://pdf.example.com/pdf"
+
+[profiles.local]
+api_key = "local_key"
+"#;
+
+fn with_config(test: impl FnOnce() -> eyre::Result<()>) -> eyre::Result<()> {
+    let config_dir = TempDir::new()?;
+    let config_path = config_dir.path().join("config.toml");
+
+    std::fs::write(&config_path, CONFIG)?;
+
+    std::env::set_var("TRUSTBLOCK_CONFIG", &config_path);
+    for var in ["TRUSTBLOCK_PROFILE", "API_KEY", "AUDIT_ENDPOINT"] {
+        std::env::remove_var(var);
+    }
+
+    let result = test();
+
+    std::env::remove_var("TRUSTBLOCK_CONFIG");
+    config::set_profile(None);
+
+    result
+}
+
+#[test]
+#[serial]
+fn test_custom_default_profile() -> eyre::Result<()> {
+    with_config(|| {
+        let settings = Settings::load()?;
+
+        assert_eq!(settings.profile, "staging");
+        assert_eq!(settings.api_key.as_deref(), Some("staging_key"));
+        assert!(settings.api_key_in_plain_text());
+        assert_eq!(settings.warnings().len(), 1);
+        assert_eq!(
+            settings.audit_endpoint,
+            "https://staging.example.com/api/audit/"
+        );
+        assert_eq!(
+            settings.audit_report_hash_endpoint,
+            "https://staging.example.com/api/audit/report-hash/"
+        );
+        assert_eq!(
+            settings.pdf_generate_endpoint,
+            "https://pdf.example.com/pdf"
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_profile_flag_and_env_overrides() -> eyre::Result<()> {
+    with_config(|| {
+        config::set_profile(Some("local".to_string()));
+        std::env::set_var("AUDIT_ENDPOINT", "http://127.0.0.1:8080/audit/");
+
+        let settings = Settings::load()?;
+
+        std::env::remove_var("AUDIT_ENDPOINT");
+
+        assert_eq!(settings.profile, "local");
+        assert_eq!(settings.audit_endpoint, "http://127.0.0.1:8080/audit/");
+        assert_eq!(
+            settings.project_slug_endpoint, "http://localhost:3000/api/project/",
+            "Built-in local profile should fill the missing endpoints"
+        );
+        assert_eq!(settings.api_key(None)?, "local_key");
+        assert_eq!(settings.api_key(Some("flag_key".to_string()))?, "flag_key");
+
+        config::set_profile(Some("missing".to_string()));
+
+        assert!(Settings::load().is_err());
+
+        assert_eq!(
+            Profile::built_in("staging").and_then(|profile| profile.api_url),
+            Some(STAGING_API_URL.to_string())
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_set_and_unset_keep_other_keys() -> eyre::Result<()> {
+    with_config(|| {
+        ConfigFile::set_value(
+            Some("staging"),
+            "audit_endpoint",
+            "https://audit.example.com/",
+        )?;
+        ConfigFile::set_value(Some("custom"), "api_url", "https://custom.example.com/api")?;
+
+        let config = std::fs::read_to_string(ConfigFile::path()?)?;
+
+        assert!(config.contains(r#"default_profile = "staging""#));
+        assert!(config.contains(r#"api_key = "local_key""#));
+        assert!(config.contains("[profiles.custom]"));
+
+        let layers = Layers::load()?;
+
+        assert_eq!(
+            layers.lookup("audit_endpoint"),
+            Some((
+                "https://audit.example.com/".to_string(),
+                Source::Profile("staging".to_string())
+            ))
+        );
+        assert_eq!(
+            layers
+                .lookup("pdf_generate_endpoint")
+                .map(|(_, source)| source),
+            Some(Source::Profile("staging".to_string()))
+        );
+
+        std::env::set_var("API_KEY", "env_key");
+        assert_eq!(
+            Layers::load()?.lookup("api_key"),
+            Some(("env_key".to_string(), Source::Env("API_KEY")))
+        );
+        std::env::remove_var("API_KEY");
+
+        ConfigFile::unset_value(Some("staging"), "audit_endpoint")?;
+        ConfigFile::unset_value(Some("staging"), "pdf_generate_endpoint")?;
+
+        let layers = Layers::load()?;
+
+        assert_eq!(
+            layers.lookup("audit_endpoint").map(|(_, source)| source),
+            Some(Source::Profile("staging".to_string())),
+            "Endpoint should be derived from the profile api_url"
+        );
+        assert_eq!(
+            layers
+                .lookup("pdf_generate_endpoint")
+                .map(|(_, source)| source),
+            Some(Source::Default)
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_dotenv_ranks_below_profile() -> eyre::Result<()> {
+    with_config(|| {
+        let home_dir = TempDir::new()?;
+        let home = std::env::var_os("HOME");
+
+        std::fs::create_dir(home_dir.path().join(".trustblock"))?;
+        std::fs::write(
+            home_dir.path().join(".trustblock/.env"),
+            "API_KEY=dotenv_key\nMAX_RETRIES=7\n",
+        )?;
+        std::env::set_var("HOME", home_dir.path());
+
+        let layers = Layers::load();
+
+        for var in ["API_KEY", "MAX_RETRIES"] {
+            std::env::remove_var(var);
+        }
+        match home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+
+        let layers = layers?;
+
+        assert_eq!(
+            layers.lookup("api_key"),
+            Some((
+                "staging_key".to_string(),
+                Source::Profile("staging".to_string())
+            ))
+        );
+
+        std::env::set_var("MAX_RETRIES", "7");
+        let max_retries = layers.lookup("max_retries");
+        std::env::remove_var("MAX_RETRIES");
+
+        assert_eq!(
+            max_retries,
+            Some(("7".to_string(), Source::DotEnv("MAX_RETRIES")))
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_unquoted_numbers_and_booleans() -> eyre::Result<()> {
+    with_config(|| {
+        std::fs::write(
+            ConfigFile::path()?,
+            "[profiles.tuned]\ntimeout_secs = 30\nmax_retries = 5\ninsecure = true\nretry_jitter = false\n",
+        )?;
+        config::set_profile(Some("tuned".to_string()));
+
+        let settings = Settings::load()?;
+
+        assert_eq!(settings.http.timeout, Duration::from_secs(30));
+        assert_eq!(settings.retry.max_retries, 5);
+        assert!(settings.http.insecure);
+        assert!(!settings.retry.jitter);
+
+        ConfigFile::set_value(Some("tuned"), "connect_timeout_secs", "5")?;
+        ConfigFile::set_value(Some("tuned"), "update_check", "false")?;
+
+        let config = std::fs::read_to_string(ConfigFile::path()?)?;
+
+        assert!(config.contains("connect_timeout_secs = 5\n"));
+        assert!(config.contains("update_check = false\n"));
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_invalid_value_names_key_and_source() -> eyre::Result<()> {
+    with_config(|| {
+        std::fs::write(
+            ConfigFile::path()?,
+            "[profiles.tuned]\ntimeout_secs = \"soon\"\n",
+        )?;
+        config::set_profile(Some("tuned".to_string()));
+
+        let error = Settings::load().expect_err("invalid timeout should fail");
+        assert_eq!(
+            error.to_string(),
+            r#"Invalid timeout_secs "soon" from profile tuned"#
+        );
+
+        std::fs::write(ConfigFile::path()?, "[profiles.tuned]\ntimeout_secs = 30\n")?;
+        std::env::set_var("TRUSTBLOCK_INSECURE", "maybe");
+        let error = Settings::load().map(|_| ());
+        std::env::remove_var("TRUSTBLOCK_INSECURE");
+
+        assert!(error
+            .expect_err("invalid insecure should fail")
+            .to_string()
+            .contains("from env var TRUSTBLOCK_INSECURE"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_keys_match_profile_fields() -> eyre::Result<()> {
+    let profile = KEYS
+        .iter()
+        .map(|key| format!("{} = \"{}\"\n", key.name, key.name))
+        .collect::<String>();
+    let profile = toml::from_str::<Profile>(&profile)?;
+
+    for key in KEYS {
+        assert_eq!(profile.get(key.name).map(String::as_str), Some(key.name));
+    }
+
+    assert_eq!(
+        config::key("api_key").map(|key| key.kind),
+        Some(Kind::Secret)
+    );
+    assert_eq!(
+        config::key("max_retries").map(|key| key.kind),
+        Some(Kind::Number)
+    );
+    assert_eq!(
+        config::key("insecure").and_then(|key| key.env_var),
+        Some("TRUSTBLOCK_INSECURE")
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_set_value_in_malformed_profile() -> eyre::Result<()> {
+    with_config(|| {
+        std::fs::write(ConfigFile::path()?, "profiles.production = \"x\"\n")?;
+
+        let error = ConfigFile::set_value(Some("production"), "api_url", "https://example.com")
+            .expect_err("string profile should not be edited");
+        assert!(format!("{error:#}").contains(r#"Profile "production" should be a table"#));
+
+        assert!(ConfigFile::unset_value(Some("production"), "api_url").is_err());
+        assert_eq!(
+            std::fs::read_to_string(ConfigFile::path()?)?,
+            "profiles.production = \"x\"\n"
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_config_get_masks_secrets() -> eyre::Result<()> {
+    with_config(|| {
+        let get = |args: &[&str]| -> eyre::Result<Option<String>> {
+            let Commands::Config(cmd) =
+                Cli::try_parse_from(["trustblock", "config", "get"].iter().chain(args))?.command
+            else {
+                panic!("Expected config command");
+            };
+
+            Ok(cmd.run()?.settings[0].value.clone())
+        };
+
+        assert_eq!(get(&["api_key"])?.as_deref(), Some("****_key"));
+        assert_eq!(
+            get(&["api_key", "--reveal"])?.as_deref(),
+            Some("staging_key")
+        );
+        assert_eq!(
+            get(&["pdf_generate_endpoint"])?.as_deref(),
+            Some("https://pdf.example.com/pdf")
+        );
+
+        Ok(())
+    })
+}
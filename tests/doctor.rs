@@ -0,0 +1,122 @@
+use clap::Parser;
+use mockito::{Matcher, Server};
+use serde_json::json;
+use serial_test::serial;
+use tempfile::TempDir;
+use trustblock_cli::{
+    cmd::{
+        block_on,
+        trustblock::{Cli, Commands},
+    },
+    constants::TRUSTBLOCK_API_KEY_HEADER,
+    error::ExitCode,
+    shell::{CheckStatus, CommandOutput, ResultStatus},
+};
+
+const ENDPOINT_VARS: [(&str, &str); 6] = [
+    ("AUDIT_ENDPOINT", "/audit/"),
+    ("AUDIT_REPORT_HASH_ENDPOINT", "/audit/report-hash/"),
+    ("PROJECT_SLUG_ENDPOINT", "/project/"),
+    ("WEB3_STORAGE_API_ENDPOINT", "/file/"),
+    ("PDF_GENERATE_ENDPOINT", "/pdf/"),
+    ("AUTH_ENDPOINT", "/auth/"),
+];
+
+fn run_doctor(server: &mut Server) -> eyre::Result<CommandOutput> {
+    let config_dir = TempDir::new()?;
+
+    std::env::set_var("TRUSTBLOCK_CONFIG", config_dir.path().join("config.toml"));
+    std::env::set_var("API_KEY", "api_key");
+
+    for (var, path) in ENDPOINT_VARS {
+        std::env::set_var(var, format!("{}{path}", server.url()));
+    }
+
+    // Bare endpoints are not valid requests, but any answer below 500 means
+    // the service is up
+    server
+        .mock("GET", Matcher::Any)
+        .with_status(404)
+        .expect_at_least(0)
+        .create();
+
+    let result = match Cli::try_parse_from(["trustblock", "doctor"])?.command {
+        Commands::Doctor(cmd) => block_on(cmd.run()),
+        _ => panic!("Expected doctor command"),
+    };
+
+    for (var, _) in ENDPOINT_VARS {
+        std::env::remove_var(var);
+    }
+
+    std::env::remove_var("TRUSTBLOCK_CONFIG");
+    std::env::remove_var("API_KEY");
+
+    result
+}
+
+fn check_status(output: &CommandOutput, name: &str) -> CheckStatus {
+    output
+        .checks
+        .iter()
+        .find(|check| check.name == name)
+        .unwrap_or_else(|| panic!("Missing check {name}"))
+        .status
+}
+
+#[test]
+#[serial]
+fn test_doctor_passes() -> eyre::Result<()> {
+    let mut server = Server::new();
+
+    let me = server
+        .mock("GET", "/auth/me")
+        .match_header(TRUSTBLOCK_API_KEY_HEADER, "api_key")
+        .with_body(json!({ "name": "Auditor", "email": "auditor@trustblock.run" }).to_string())
+        .create();
+
+    let output = run_doctor(&mut server)?;
+
+    me.assert();
+
+    assert_eq!(output.status, ResultStatus::Success);
+    assert_eq!(check_status(&output, "authentication"), CheckStatus::Pass);
+    assert_eq!(check_status(&output, "pdf endpoint"), CheckStatus::Pass);
+    assert_eq!(output.identity.expect("identity")["name"], "Auditor");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_doctor_reports_failures() -> eyre::Result<()> {
+    let mut server = Server::new();
+
+    server
+        .mock("GET", "/auth/me")
+        .with_status(401)
+        .with_body(json!({ "message": "Invalid API key" }).to_string())
+        .create();
+
+    server.mock("GET", "/pdf/").with_status(503).create();
+
+    let output = run_doctor(&mut server)?;
+
+    assert_eq!(output.status, ResultStatus::Error);
+    assert_eq!(output.exit_code, Some(ExitCode::Failure as i32));
+    assert_eq!(check_status(&output, "API key"), CheckStatus::Pass);
+    assert_eq!(check_status(&output, "authentication"), CheckStatus::Fail);
+    assert_eq!(check_status(&output, "pdf endpoint"), CheckStatus::Fail);
+    assert_eq!(check_status(&output, "audit endpoint"), CheckStatus::Pass);
+
+    let fix = output
+        .checks
+        .iter()
+        .find(|check| check.name == "authentication")
+        .and_then(|check| check.fix.clone())
+        .expect("fix");
+
+    assert!(fix.contains("trustblock login"));
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::json;
+use trustblock_cli::error::{ExitCode, TrustblockError};
+
+#[test]
+fn test_error_from_response_duplicates() {
+    let error = TrustblockError::from_parts(
+        StatusCode::BAD_REQUEST,
+        &json!({ "error": "Report hash is not a unique value." }),
+        None,
+    );
+
+    assert!(matches!(error, TrustblockError::DuplicateReport));
+
+    let error = TrustblockError::from_parts(
+        StatusCode::BAD_REQUEST,
+        &json!({ "error": "Project domain is not a unique value." }),
+        None,
+    );
+
+    assert!(matches!(error, TrustblockError::DuplicateProject));
+}
+
+#[test]
+fn test_error_from_response_validation_details() {
+    let error = TrustblockError::from_parts(
+        StatusCode::BAD_REQUEST,
+        &json!({
+            "error": "Invalid audit data",
+            "details": [
+                { "path": ["project", "links", "twitter"], "message": "invalid url" },
+                { "path": "name", "message": "required" }
+            ]
+        }),
+        None,
+    );
+
+    let TrustblockError::Validation { message, fields } = &error else {
+        panic!("Expected validation error, got {error:?}");
+    };
+
+    assert_eq!(message, "Invalid audit data");
+    assert_eq!(fields.len(), 2);
+    assert_eq!(
+        error.to_string(),
+        "Invalid audit data:\n  project.links.twitter: invalid url\n  name: required"
+    );
+}
+
+#[test]
+fn test_error_from_response_status_classes() {
+    let error = TrustblockError::from_parts(
+        StatusCode::UNAUTHORIZED,
+        &json!({ "error": "Invalid API key" }),
+        None,
+    );
+
+    assert!(matches!(error, TrustblockError::Unauthorized { .. }));
+
+    let error = TrustblockError::from_parts(
+        StatusCode::TOO_MANY_REQUESTS,
+        &json!(null),
+        Some(Duration::from_secs(30)),
+    );
+
+    assert_eq!(
+        error.to_string(),
+        "Rate limited by Trustblock API. Retry after 30s"
+    );
+
+    let error = TrustblockError::from_parts(
+        StatusCode::BAD_GATEWAY,
+        &json!("upstream unavailable"),
+        None,
+    );
+
+    assert!(
+        matches!(error, TrustblockError::Server { status, ref message } if status == StatusCode::BAD_GATEWAY && message == "upstream unavailable")
+    );
+
+    let error = TrustblockError::from_parts(StatusCode::NOT_FOUND, &json!({}), None);
+
+    assert!(matches!(error, TrustblockError::Rejected { .. }));
+}
+
+#[test]
+fn test_exit_code_from_report() {
+    let report = eyre::Report::new(TrustblockError::DuplicateReport);
+
+    assert_eq!(ExitCode::from_report(&report), ExitCode::Duplicate);
+
+    let report = eyre::Report::new(TrustblockError::Unauthorized {
+        message: "Invalid API key".to_string(),
+    })
+    .wrap_err("Could not publish to DB");
+
+    assert_eq!(ExitCode::from_report(&report), ExitCode::Authentication);
+
+    let error = TrustblockError::StorageRejected {
+        store: "s3",
+        status: StatusCode::FORBIDDEN,
+        message: "AccessDenied".to_string(),
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "Report upload rejected by s3 (403 Forbidden): AccessDenied"
+    );
+    assert_eq!(
+        ExitCode::from_report(&eyre::Report::new(error)),
+        ExitCode::ServerRejected
+    );
+
+    let report =
+        eyre::Report::new(serde_json::from_str::<serde_json::Value>("{").expect_err("should fail"));
+
+    assert_eq!(ExitCode::from_report(&report), ExitCode::InvalidInput);
+
+    assert_eq!(
+        ExitCode::from_report(&eyre::eyre!("Something went wrong")),
+        ExitCode::Failure
+    );
+}
@@ -0,0 +1,186 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::mpsc,
+    time::Duration,
+};
+
+use serial_test::serial;
+use tempfile::TempDir;
+use trustblock_cli::{
+    cmd::block_on,
+    config::{self, Layers, Settings, Source},
+    http::HttpSettings,
+};
+
+const CA_BUNDLE: &str = "tests/test-data/ca-bundle.pem";
+
+#[test]
+fn test_ca_bundle() -> eyre::Result<()> {
+    let http = HttpSettings {
+        ca_bundle: Some(CA_BUNDLE.into()),
+        ..HttpSettings::default()
+    };
+
+    http.client()?;
+
+    let dir = TempDir::new()?;
+    let empty_bundle = dir.path().join("empty.pem");
+    std::fs::write(&empty_bundle, "no certificate here")?;
+
+    for ca_bundle in [empty_bundle, dir.path().join("missing.pem")] {
+        let http = HttpSettings {
+            ca_bundle: Some(ca_bundle),
+            ..HttpSettings::default()
+        };
+
+        assert!(http.client().is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_timeout() -> eyre::Result<()> {
+    // Accepts connections and never answers
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    std::thread::spawn(move || {
+        let _streams = listener.incoming().collect::<Vec<_>>();
+    });
+
+    let client = HttpSettings {
+        timeout: Duration::from_millis(200),
+        ..HttpSettings::default()
+    }
+    .client()?;
+
+    let error =
+        block_on(async { client.get(url).send().await }).expect_err("request should time out");
+
+    assert!(error.is_timeout());
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_client_has_no_total_timeout() -> eyre::Result<()> {
+    // Answers after longer than the timeout of API requests
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+
+            std::thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().expect("stream clone"));
+                let mut line = String::new();
+
+                while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                    line.clear();
+                }
+
+                std::thread::sleep(Duration::from_millis(500));
+
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+            });
+        }
+    });
+
+    let http = HttpSettings {
+        timeout: Duration::from_millis(200),
+        ..HttpSettings::default()
+    };
+
+    let client = http.client()?;
+    let error =
+        block_on(async { client.get(&url).send().await }).expect_err("API request should time out");
+
+    assert!(error.is_timeout());
+
+    let transfer = http.transfer_client()?;
+    let body = block_on(async { transfer.get(&url).send().await?.text().await })?;
+
+    assert_eq!(body, "ok");
+
+    Ok(())
+}
+
+#[test]
+fn test_proxy() -> eyre::Result<()> {
+    // Stand-in proxy that records the request line it receives
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let proxy_url = format!("http://{}", listener.local_addr()?);
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        if let Some(Ok(mut stream)) = listener.incoming().next() {
+            let mut request_line = String::new();
+            let _ = BufReader::new(&stream).read_line(&mut request_line);
+            let _ = stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n");
+            let _ = sender.send(request_line);
+        }
+    });
+
+    let client = HttpSettings {
+        proxy_url: Some(proxy_url),
+        ..HttpSettings::default()
+    }
+    .client()?;
+
+    let response = block_on(async { client.get("http://trustblock.invalid/api/").send().await })?;
+
+    assert_eq!(response.status(), 204);
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_secs(5))?.trim(),
+        "GET http://trustblock.invalid/api/ HTTP/1.1"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_network_flags_override_profile() -> eyre::Result<()> {
+    let dir = TempDir::new()?;
+    let config_path = dir.path().join("config.toml");
+
+    std::fs::write(
+        &config_path,
+        "[profiles.production]\ntimeout_secs = \"30\"\nproxy_url = \"http://proxy.example.com:3128\"\n",
+    )?;
+
+    std::env::set_var("TRUSTBLOCK_CONFIG", &config_path);
+
+    let http = Settings::load()?.http;
+
+    assert_eq!(http.timeout, Duration::from_secs(30));
+    assert_eq!(
+        http.connect_timeout,
+        HttpSettings::default().connect_timeout
+    );
+    assert_eq!(
+        http.proxy_url.as_deref(),
+        Some("http://proxy.example.com:3128")
+    );
+
+    config::set_flag("timeout_secs", Some("5".to_string()));
+    config::set_flag("insecure", Some("true".to_string()));
+
+    let http = Settings::load()?.http;
+
+    assert_eq!(http.timeout, Duration::from_secs(5));
+    assert!(http.insecure);
+    assert_eq!(
+        Layers::load()?.lookup("timeout_secs"),
+        Some(("5".to_string(), Source::Flag))
+    );
+
+    config::set_flag("timeout_secs", None);
+    config::set_flag("insecure", None);
+    std::env::remove_var("TRUSTBLOCK_CONFIG");
+
+    Ok(())
+}
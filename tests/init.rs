@@ -72,16 +72,15 @@ fn test_init_args_success() -> eyre::Result<()> {
 
     let trustblock_dir = home_dir.join(CLI_PATH);
 
-    let env_path = trustblock_dir.join(".env");
+    let config_path = trustblock_dir.join("config.toml");
 
     let init_fixture_path = format!("{}{}", FIXTURES_DIR, "init.stdout");
-    let env_fixture_path = format!("{}{}", FIXTURES_DIR, "env_file_with_values.stdout");
 
     //Write to fixture file
     std::fs::write(
         &init_fixture_path,
         format!(
-            "Generating .trustblock folder...\n\nCreated .env file at \"{}/.env\"\n",
+            "Generating .trustblock folder...\n\nCreated .env file at \"{}/.env\"\nStored encrypted API key in profile production of {config_path:?}\n",
             trustblock_dir.to_string_lossy()
         ),
     )?;
@@ -90,6 +89,7 @@ fn test_init_args_success() -> eyre::Result<()> {
         .arg("init")
         .arg("-a")
         .arg("some_api_key")
+        .env("TRUSTBLOCK_PASSPHRASE", "passphrase")
         .assert()
         .success()
         .stdout(predicate::path::eq_file(init_fixture_path));
@@ -97,8 +97,11 @@ fn test_init_args_success() -> eyre::Result<()> {
     // Checks if .trustblock folder exists
     predicate::path::exists().eval(&trustblock_dir);
 
-    // Checks if .env file is equal to fixture
-    predicate::path::eq_file(env_fixture_path).eval(env_path.as_path());
+    // Checks the API key is only stored encrypted
+    let config = std::fs::read_to_string(config_path)?;
+
+    assert!(config.contains(r#"api_key = "enc:v1:"#));
+    assert!(!config.contains("some_api_key"));
 
     Ok(())
 }
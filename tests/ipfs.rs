@@ -0,0 +1,77 @@
+use cid::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
+use tempfile::NamedTempFile;
+use trustblock_cli::ipfs::{file_cid, CHUNK_SIZE};
+
+#[test]
+fn test_file_cid_single_chunk() -> eyre::Result<()> {
+    let file = NamedTempFile::new()?;
+
+    assert_eq!(
+        file_cid(file.path())?,
+        "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+        "Empty file should be a single raw leaf"
+    );
+
+    std::fs::write(file.path(), "Trustblock report")?;
+
+    assert_eq!(
+        file_cid(file.path())?,
+        "bafkreiaig4kyxcktemu6oumtjj3qlpuivo5m6ieybskyxng2qsxqzyyt44"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_file_cid_multiple_chunks() -> eyre::Result<()> {
+    let file = NamedTempFile::new()?;
+
+    std::fs::write(file.path(), vec![7; CHUNK_SIZE + 1])?;
+
+    let full_leaf = Cid::new_v1(0x55, Code::Sha2_256.digest(&vec![7; CHUNK_SIZE]));
+    let last_leaf = Cid::new_v1(0x55, Code::Sha2_256.digest(&[7]));
+
+    // Root node as go-ipfs encodes it: a link per leaf with an empty name and
+    // the leaf size, then the UnixFS file data with the file and block sizes
+    let root = [
+        &[0x12, 0x2c, 0x0a, 0x24][..],
+        &full_leaf.to_bytes(),
+        &[0x12, 0x00, 0x18, 0x80, 0x80, 0x40],
+        &[0x12, 0x2a, 0x0a, 0x24],
+        &last_leaf.to_bytes(),
+        &[0x12, 0x00, 0x18, 0x01],
+        &[
+            0x0a, 0x0c, 0x08, 0x02, 0x18, 0x81, 0x80, 0x40, 0x20, 0x80, 0x80, 0x40, 0x20, 0x01,
+        ],
+    ]
+    .concat();
+
+    let cid = file_cid(file.path())?;
+
+    assert_eq!(
+        cid,
+        Cid::new_v1(0x70, Code::Sha2_256.digest(&root)).to_string()
+    );
+
+    // `ipfs add --only-hash --cid-version 1 --raw-leaves --chunker size-1048576`
+    assert_eq!(
+        cid,
+        "bafybeigjlpad4kropgqbvvtp4leb45zzlmlgtwcnswuiq4au36xg6a4ibq"
+    );
+
+    let data = (0..2 * CHUNK_SIZE + 100)
+        .map(|index| (index % 251) as u8)
+        .collect::<Vec<u8>>();
+
+    std::fs::write(file.path(), data)?;
+
+    assert_eq!(
+        file_cid(file.path())?,
+        "bafybeidbpvykjlnsz7xn23anxb6xtriq3beitet4jblqcan643oc6td6ke"
+    );
+
+    Ok(())
+}
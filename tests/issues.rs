@@ -0,0 +1,86 @@
+mod common;
+
+use common::constants::AUDIT_JSON_PATH;
+use proptest::prelude::*;
+use serde_json::json;
+use strum::IntoEnumIterator;
+use trustblock_cli::{
+    types::{
+        deserialize_issues, get_issue_tallies, get_transformed_issues, Audit, IssueCount, Severity,
+        Status,
+    },
+    utils::parse_json,
+};
+
+/// Issue counts with every status and severity below `max_count`.
+fn issue_count(max_count: u32) -> impl Strategy<Value = IssueCount> {
+    let buckets = Status::iter().count() * Severity::iter().count();
+
+    proptest::collection::vec(0..max_count, buckets).prop_map(|counts| {
+        let mut issues = IssueCount::default();
+        let mut counts = counts.into_iter();
+
+        for status in Status::iter() {
+            for severity in Severity::iter() {
+                *issues.severity_count_mut(status).count_mut(severity) =
+                    counts.next().unwrap_or_default();
+            }
+        }
+
+        issues
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_tallies_round_trip(issues in issue_count(100_000)) {
+        prop_assert_eq!(IssueCount::from_tallies(get_issue_tallies(&issues)), issues);
+    }
+
+    #[test]
+    fn test_transformed_issues_round_trip(issues in issue_count(5)) {
+        prop_assert_eq!(IssueCount::from_issues(&get_transformed_issues(&issues)), issues);
+    }
+
+    #[test]
+    fn test_deserialize_issues_forms(issues in issue_count(5)) {
+        let forms = [
+            serde_json::to_value(issues)?,
+            serde_json::to_value(get_issue_tallies(&issues))?,
+            serde_json::to_value(get_transformed_issues(&issues))?,
+        ];
+
+        for form in forms {
+            prop_assert_eq!(deserialize_issues(form)?, issues);
+        }
+    }
+
+    #[test]
+    fn test_audit_wire_format_round_trip(issues in issue_count(100_000)) {
+        let audit = Audit {
+            issues,
+            ..parse_json::<Audit>(&AUDIT_JSON_PATH.into()).expect("audit.json should parse")
+        };
+
+        let payload = serde_json::to_value(&audit)?;
+        let read_back = serde_json::from_value::<Audit>(payload.clone())?;
+
+        prop_assert_eq!(read_back.issues, issues);
+        prop_assert_eq!(serde_json::to_value(&read_back)?, payload);
+    }
+}
+
+#[test]
+fn test_deserialize_issues_errors() {
+    let error = deserialize_issues(json!({ "FIXED": { "LOW": "many" } }))
+        .expect_err("count should be a number");
+
+    assert!(error
+        .to_string()
+        .starts_with("invalid type: string \"many\""));
+
+    let error = deserialize_issues(json!([{ "status": "FIXED", "severity": "SEVERE" }]))
+        .expect_err("severity should be known");
+
+    assert!(error.to_string().starts_with("unknown variant `SEVERE`"));
+}
@@ -0,0 +1,56 @@
+use tempfile::tempdir;
+use trustblock_cli::{cmd::publish_audit::ReportSource, journal::PublishJournal};
+
+#[test]
+fn test_journal_resume() -> eyre::Result<()> {
+    let dir = tempdir()?;
+    let journal_dir = dir.path().join("journal");
+
+    let audit_file = dir.path().join("audit.json");
+    let report_file = dir.path().join("report.pdf");
+
+    std::fs::write(&audit_file, "{}")?;
+    std::fs::write(&report_file, "report")?;
+
+    let report = ReportSource::Pdf(report_file.clone());
+
+    let mut journal = PublishJournal::open_in(&journal_dir, &audit_file, &report, false)?;
+
+    assert_eq!(journal.uploaded_report(), None);
+
+    journal.project_resolved = true;
+    journal.report_cid = Some("bafkreicid".to_string());
+    journal.report_url = Some("https://bafkreicid.ipfs.w3s.link".to_string());
+    journal.save()?;
+
+    // Resuming loads the completed steps
+    let resumed = PublishJournal::open_in(&journal_dir, &audit_file, &report, true)?;
+
+    assert_eq!(resumed, journal);
+
+    // Without resume the publish starts over
+    let fresh = PublishJournal::open_in(&journal_dir, &audit_file, &report, false)?;
+
+    assert!(!fresh.project_resolved);
+    assert_eq!(fresh.uploaded_report(), None);
+
+    // Fixing the audit file keeps the journal
+    std::fs::write(&audit_file, r#"{"fixed":true}"#)?;
+
+    let fixed = PublishJournal::open_in(&journal_dir, &audit_file, &report, true)?;
+
+    assert_eq!(fixed, journal);
+
+    // A different report has its own journal
+    std::fs::write(&report_file, "another report")?;
+
+    let other = PublishJournal::open_in(&journal_dir, &audit_file, &report, true)?;
+
+    assert_eq!(other.uploaded_report(), None);
+
+    resumed.remove()?;
+
+    assert_eq!(std::fs::read_dir(&journal_dir)?.count(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use clap::Parser;
+use mockito::{Matcher, Server};
+use serde_json::json;
+use serial_test::serial;
+use tempfile::TempDir;
+use trustblock_cli::{
+    cmd::{
+        block_on,
+        trustblock::{Cli, Commands},
+    },
+    config::ConfigFile,
+    error::ExitCode,
+    shell::CommandOutput,
+    vault::decrypt,
+};
+
+fn run(args: &[&str]) -> eyre::Result<CommandOutput> {
+    match Cli::try_parse_from(args)?.command {
+        Commands::Login(cmd) => block_on(cmd.run()),
+        Commands::Logout(cmd) => block_on(cmd.run()),
+        _ => panic!("Expected login or logout command"),
+    }
+}
+
+fn with_auth_server(test: impl FnOnce(&mut Server) -> eyre::Result<()>) -> eyre::Result<()> {
+    let mut server = Server::new();
+    let config_dir = TempDir::new()?;
+
+    std::env::set_var("TRUSTBLOCK_CONFIG", config_dir.path().join("config.toml"));
+    std::env::set_var("AUTH_ENDPOINT", format!("{}/auth/", server.url()));
+    std::env::set_var("TRUSTBLOCK_PASSPHRASE", "passphrase");
+    std::env::remove_var("API_KEY");
+
+    server
+        .mock("POST", "/auth/device/code")
+        .with_body(
+            json!({
+                "deviceCode": "device_code",
+                "userCode": "ABCD-EFGH",
+                "verificationUri": "https://trustblock.run/device",
+                "expiresIn": 60,
+                "interval": 0
+            })
+            .to_string(),
+        )
+        .create();
+
+    let result = test(&mut server);
+
+    for var in [
+        "TRUSTBLOCK_CONFIG",
+        "AUTH_ENDPOINT",
+        "TRUSTBLOCK_PASSPHRASE",
+    ] {
+        std::env::remove_var(var);
+    }
+
+    result
+}
+
+#[test]
+#[serial]
+fn test_login_and_logout() -> eyre::Result<()> {
+    with_auth_server(|server| {
+        let token = server
+            .mock("POST", "/auth/device/token")
+            .match_body(Matcher::PartialJson(json!({ "deviceCode": "device_code" })))
+            .with_body(json!({ "accessToken": "issued_api_key" }).to_string())
+            .create();
+
+        run(&["trustblock", "login", "--no-browser"])?;
+
+        token.assert();
+
+        let api_key = ConfigFile::load()?.profiles["production"]
+            .api_key
+            .clone()
+            .expect("API key should be stored");
+
+        assert_eq!(decrypt(&api_key, "passphrase")?, "issued_api_key");
+
+        let revoke = server
+            .mock("POST", "/auth/revoke")
+            .match_header("x-trustblock-api-key", "issued_api_key")
+            .create();
+
+        run(&["trustblock", "logout"])?;
+
+        revoke.assert();
+
+        assert_eq!(ConfigFile::load()?.profiles["production"].api_key, None);
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_login_denied() -> eyre::Result<()> {
+    with_auth_server(|server| {
+        server
+            .mock("POST", "/auth/device/token")
+            .with_status(400)
+            .with_body(json!({ "error": "access_denied" }).to_string())
+            .create();
+
+        let error = run(&["trustblock", "login", "--no-browser"]).expect_err("Login is denied");
+
+        assert_eq!(ExitCode::from_report(&error), ExitCode::Authentication);
+        assert_eq!(ConfigFile::load()?.profiles.get("production"), None);
+
+        Ok(())
+    })
+}
+
+#[test]
+#[serial]
+fn test_login_retries_unavailable_server() -> eyre::Result<()> {
+    with_auth_server(|server| {
+        std::env::set_var("RETRY_BACKOFF_MS", "1");
+
+        let unavailable = server
+            .mock("POST", "/auth/device/token")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let token = server
+            .mock("POST", "/auth/device/token")
+            .with_body(json!({ "accessToken": "issued_api_key" }).to_string())
+            .create();
+
+        let result = run(&["trustblock", "login", "--no-browser"]);
+
+        std::env::remove_var("RETRY_BACKOFF_MS");
+        result?;
+
+        unavailable.assert();
+        token.assert();
+
+        Ok(())
+    })
+}
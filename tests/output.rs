@@ -0,0 +1,47 @@
+use serde_json::json;
+use trustblock_cli::{error::TrustblockError, shell::CommandOutput};
+
+#[test]
+fn test_command_output_success_json() -> eyre::Result<()> {
+    let output = CommandOutput {
+        report_cid: Some("bafkreicid".to_string()),
+        report_url: Some("https://bafkreicid.ipfs.w3s.link".to_string()),
+        audit_id: Some("audit_id".to_string()),
+        warnings: vec!["warning".to_string()],
+        ..CommandOutput::new("publish-audit")
+    };
+
+    assert_eq!(
+        serde_json::to_value(output)?,
+        json!({
+            "command": "publish-audit",
+            "status": "success",
+            "reportCid": "bafkreicid",
+            "reportUrl": "https://bafkreicid.ipfs.w3s.link",
+            "auditId": "audit_id",
+            "warnings": ["warning"]
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_command_output_error_json() -> eyre::Result<()> {
+    let report = eyre::Report::new(TrustblockError::DuplicateReport);
+
+    let output = CommandOutput::error("publish-audit", &report);
+
+    assert_eq!(
+        serde_json::to_value(output)?,
+        json!({
+            "command": "publish-audit",
+            "status": "error",
+            "warnings": [],
+            "error": "Audit already published: report hash is not a unique value",
+            "exitCode": 5
+        })
+    );
+
+    Ok(())
+}
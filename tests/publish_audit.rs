@@ -10,7 +10,10 @@ use common::{
 use ethers_core::types::Address;
 use predicates::prelude::*;
 use sqlx::{mysql::MySqlRow, Row};
-use trustblock_cli::types::{AuditContract, Chains, IssueCount, Links, SeverityCount};
+use trustblock_cli::{
+    error::ExitCode,
+    types::{AuditContract, Chains, IssueCount, IssueTally, Links},
+};
 
 #[tokio::test]
 async fn test_publish_audit_db() -> eyre::Result<()> {
@@ -57,8 +60,10 @@ async fn test_publish_audit_db() -> eyre::Result<()> {
         .arg("-r")
         .arg(&pdf_file)
         .assert()
-        .success()
-        .stdout(predicate::str::is_match(pattern)?);
+        .failure()
+        .code(ExitCode::Duplicate as i32)
+        .stdout(predicate::str::is_match(pattern)?)
+        .stderr(predicate::str::contains("Audit already published"));
 
     let pattern = std::fs::read_to_string(args_report_and_url_together_error_fixture)?;
 
@@ -296,45 +301,13 @@ async fn test_publish_audit_db() -> eyre::Result<()> {
     .fetch_all(&pool)
     .await?;
 
-    let (fixed, risk_accepted) = fetched_issue_count.into_iter().fold(
-        (SeverityCount::default(), SeverityCount::default()),
-        |(mut fixed, mut risk_accepted), result| {
-            let count = result.count;
-
-            match (result.status.as_str(), result.severity.as_str()) {
-                ("FIXED", "LOW") => {
-                    fixed.low = count as u8;
-                }
-                ("FIXED", "MEDIUM") => {
-                    fixed.medium = count as u8;
-                }
-                ("FIXED", "HIGH") => {
-                    fixed.high = count as u8;
-                }
-                ("FIXED", "CRITICAL") => {
-                    fixed.critical = count as u8;
-                }
-                ("RISK_ACCEPTED", "LOW") => {
-                    risk_accepted.low = count as u8;
-                }
-                ("RISK_ACCEPTED", "MEDIUM") => {
-                    risk_accepted.medium = count as u8;
-                }
-                ("RISK_ACCEPTED", "HIGH") => {
-                    risk_accepted.high = count as u8;
-                }
-                ("RISK_ACCEPTED", "CRITICAL") => {
-                    risk_accepted.critical = count as u8;
-                }
-                _ => (),
-            }
-
-            (fixed, risk_accepted)
-        },
+    let fetched_issue_count = IssueCount::from_tallies(
+        fetched_issue_count
+            .iter()
+            .map(SQLIssueTempResult::tally)
+            .collect::<eyre::Result<Vec<IssueTally>>>()?,
     );
 
-    let fetched_issue_count = IssueCount::new(fixed, risk_accepted);
-
     assert!(
         audit.issues == fetched_issue_count,
         "Issue counts are not equal"
@@ -355,45 +328,13 @@ async fn test_publish_audit_db() -> eyre::Result<()> {
     .fetch_all(&pool)
     .await?;
 
-    let (fixed, risk_accepted) = fetched_issue_count_web.into_iter().fold(
-        (SeverityCount::default(), SeverityCount::default()),
-        |(mut fixed, mut risk_accepted), result| {
-            let count = result.count;
-
-            match (result.status.as_str(), result.severity.as_str()) {
-                ("FIXED", "LOW") => {
-                    fixed.low = count as u8;
-                }
-                ("FIXED", "MEDIUM") => {
-                    fixed.medium = count as u8;
-                }
-                ("FIXED", "HIGH") => {
-                    fixed.high = count as u8;
-                }
-                ("FIXED", "CRITICAL") => {
-                    fixed.critical = count as u8;
-                }
-                ("RISK_ACCEPTED", "LOW") => {
-                    risk_accepted.low = count as u8;
-                }
-                ("RISK_ACCEPTED", "MEDIUM") => {
-                    risk_accepted.medium = count as u8;
-                }
-                ("RISK_ACCEPTED", "HIGH") => {
-                    risk_accepted.high = count as u8;
-                }
-                ("RISK_ACCEPTED", "CRITICAL") => {
-                    risk_accepted.critical = count as u8;
-                }
-                _ => (),
-            }
-
-            (fixed, risk_accepted)
-        },
+    let fetched_issue_count_web = IssueCount::from_tallies(
+        fetched_issue_count_web
+            .iter()
+            .map(SQLIssueTempResult::tally)
+            .collect::<eyre::Result<Vec<IssueTally>>>()?,
     );
 
-    let fetched_issue_count_web = IssueCount::new(fixed, risk_accepted);
-
     assert!(
         web_audit.issues == fetched_issue_count_web,
         "Issue counts are not equal web"
@@ -401,3 +342,30 @@ async fn test_publish_audit_db() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_publish_audit_dry_run() -> eyre::Result<()> {
+    let (pdf_file, audit_file, audit) = generate_random_data(None)?;
+
+    // Dry run prints the payload and never touches the API
+    Command::cargo_bin("trustblock")?
+        .arg("publish-audit")
+        .arg("-a")
+        .arg(&audit_file)
+        .arg("-r")
+        .arg(&pdf_file)
+        .arg("--dry-run")
+        .arg("-k")
+        .arg("invalid_api_key")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Dry run: nothing was uploaded or published",
+        ))
+        .stdout(predicate::str::contains(&audit.name));
+
+    clean_temp_file(audit_file)?;
+    clean_temp_file(pdf_file)?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use tempfile::tempdir;
+use trustblock_cli::cmd::publish_batch::{parse_manifest, ManifestEntry};
+
+fn expected_entries(base_dir: &Path) -> Vec<ManifestEntry> {
+    vec![
+        ManifestEntry {
+            audit: base_dir.join("audits/first.json"),
+            pdf: Some(base_dir.join("reports/first.pdf")),
+            url: None,
+        },
+        ManifestEntry {
+            audit: base_dir.join("audits/second.json"),
+            pdf: None,
+            url: Some("https://trustblock.run/".to_string()),
+        },
+    ]
+}
+
+#[test]
+fn test_parse_manifest_formats() -> eyre::Result<()> {
+    let dir = tempdir()?;
+
+    let manifests = [
+        (
+            "manifest.yaml",
+            "- audit: audits/first.json\n  pdf: reports/first.pdf\n- audit: audits/second.json\n  url: https://trustblock.run/\n",
+        ),
+        (
+            "manifest.json",
+            r#"[{"audit":"audits/first.json","pdf":"reports/first.pdf"},{"audit":"audits/second.json","url":"https://trustblock.run/"}]"#,
+        ),
+        (
+            "manifest.csv",
+            "audit,pdf,url\naudits/first.json,reports/first.pdf,\naudits/second.json,,https://trustblock.run/\n",
+        ),
+    ];
+
+    for (name, content) in manifests {
+        let path = dir.path().join(name);
+
+        std::fs::write(&path, content)?;
+
+        assert_eq!(
+            parse_manifest(&path)?,
+            expected_entries(dir.path()),
+            "Unexpected entries in {name}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_manifest_unsupported_format() -> eyre::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("manifest.txt");
+
+    std::fs::write(&path, "audits/first.json")?;
+
+    assert!(parse_manifest(&path).is_err());
+
+    Ok(())
+}
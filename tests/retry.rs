@@ -0,0 +1,308 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::{header::HeaderMap, Client, StatusCode};
+use trustblock_cli::{
+    client::TrustblockClient,
+    cmd::block_on,
+    config::{Profile, Settings},
+    retry::{retry_after, RetryPolicy},
+    types::Audit,
+    utils::parse_json,
+};
+
+/// Status, headers and body of a canned response.
+type CannedResponse<'a> = (u16, &'a [(&'a str, &'a str)], &'a str);
+
+/// Local stand-in for a flaky service: answers each request with the next
+/// response of the list, repeating the last one, and records the requests.
+struct FlakyServer {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FlakyServer {
+    fn start(responses: &[CannedResponse]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind");
+        let url = format!("http://{}", listener.local_addr().expect("local address"));
+
+        let responses = responses
+            .iter()
+            .map(|(status, headers, body)| {
+                let headers = headers
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}\r\n"))
+                    .collect::<String>();
+
+                format!(
+                    "HTTP/1.1 {status} Status\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            })
+            .collect::<Vec<String>>();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+
+        std::thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { break };
+
+                let mut reader = BufReader::new(stream.try_clone().expect("stream clone"));
+                let mut head = String::new();
+                let mut content_length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap_or(0);
+                    }
+
+                    head.push_str(&line);
+                }
+
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+
+                recorded.lock().expect("requests lock").push(head);
+
+                let response = &responses[index.min(responses.len() - 1)];
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.lock().expect("requests lock").len()
+    }
+}
+
+fn policy(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_secs(5),
+        jitter: false,
+    }
+}
+
+#[test]
+fn test_backoff() {
+    let policy = RetryPolicy {
+        max_retries: 5,
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(3),
+        jitter: false,
+    };
+
+    assert_eq!(policy.backoff(0), Duration::from_secs(1));
+    assert_eq!(policy.backoff(1), Duration::from_secs(2));
+    assert_eq!(policy.backoff(2), Duration::from_secs(3));
+    assert_eq!(policy.backoff(40), Duration::from_secs(3));
+
+    let jittered = RetryPolicy {
+        jitter: true,
+        ..policy
+    };
+
+    for _ in 0..100 {
+        let backoff = jittered.backoff(1);
+        assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2));
+    }
+}
+
+#[test]
+fn test_retry_after() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+
+    headers.insert("retry-after", "7".parse().expect("header value"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+    // Dates in the past mean retrying right away
+    headers.insert(
+        "retry-after",
+        "Wed, 21 Oct 2015 07:28:00 GMT"
+            .parse()
+            .expect("header value"),
+    );
+    assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_retries_transient_failures() -> eyre::Result<()> {
+    let server = FlakyServer::start(&[
+        (502, &[], "bad gateway"),
+        (503, &[("Retry-After", "0")], "unavailable"),
+        (200, &[], "ok"),
+    ]);
+
+    let client = Client::new();
+    let response = block_on(policy(3).send(true, || client.get(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(server.request_count(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_gives_up_after_max_retries() -> eyre::Result<()> {
+    let server = FlakyServer::start(&[(503, &[], "unavailable")]);
+
+    let client = Client::new();
+    let response = block_on(policy(2).send(true, || client.get(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(server.request_count(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_honors_retry_after() -> eyre::Result<()> {
+    let server = FlakyServer::start(&[(429, &[("Retry-After", "1")], ""), (200, &[], "ok")]);
+
+    let client = Client::new();
+    let started = Instant::now();
+    let response = block_on(policy(1).send(true, || client.get(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(started.elapsed() >= Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_gives_up_when_retry_after_exceeds_max_backoff() -> eyre::Result<()> {
+    let server = FlakyServer::start(&[(429, &[("Retry-After", "60")], ""), (200, &[], "ok")]);
+
+    let client = Client::new();
+    let started = Instant::now();
+    let response = block_on(policy(3).send(true, || client.get(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(server.request_count(), 1);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_retry_policy_from_profile() -> eyre::Result<()> {
+    let settings = Settings::resolve(
+        "test".to_string(),
+        Profile {
+            max_retries: Some("5".to_string()),
+            retry_backoff_ms: Some("100".to_string()),
+            retry_max_backoff_ms: Some("2000".to_string()),
+            retry_jitter: Some("false".to_string()),
+            ..Profile::default()
+        },
+    )?;
+
+    assert_eq!(
+        settings.retry,
+        RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            jitter: false,
+        }
+    );
+    assert_eq!(
+        Settings::resolve("test".to_string(), Profile::default())?.retry,
+        RetryPolicy::default()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_only_idempotent_requests_are_retried() -> eyre::Result<()> {
+    let client = Client::new();
+
+    let server = FlakyServer::start(&[(502, &[], ""), (200, &[], "")]);
+    let response = block_on(policy(3).send(false, || client.get(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    assert_eq!(server.request_count(), 1);
+
+    let server = FlakyServer::start(&[(502, &[], ""), (201, &[], "")]);
+    let response = block_on(policy(3).send(true, || client.post(&server.url)))?;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(server.request_count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_audit_idempotency_key() -> eyre::Result<()> {
+    let audit = parse_json::<Audit>(&"tests/test-data/audit.json".into())?;
+
+    for enabled in [false, true] {
+        let server = FlakyServer::start(&[(502, &[], ""), (201, &[], r#"{"id":"audit_id"}"#)]);
+
+        let settings = Settings::resolve(
+            "test".to_string(),
+            Profile {
+                api_url: Some(server.url.clone()),
+                audit_idempotency_key: Some(enabled.to_string()),
+                ..Profile::default()
+            },
+        )?;
+
+        let client = TrustblockClient::new(settings, "api_key")?.with_retry_policy(policy(3));
+        let result = block_on(client.create_audit(&audit));
+
+        let requests = server.requests.lock().expect("requests lock").clone();
+
+        if enabled {
+            assert_eq!(result?.as_deref(), Some("audit_id"));
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[0].matches("idempotency-key: ").count(), 1);
+            assert_eq!(
+                requests[0].split("idempotency-key: ").nth(1),
+                requests[1].split("idempotency-key: ").nth(1),
+                "Retries should carry the same key"
+            );
+        } else {
+            assert!(result.is_err(), "Audit creation should not be retried");
+            assert_eq!(requests.len(), 1);
+            assert!(!requests[0].contains("idempotency-key"));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_client_retries_with_profile_policy() -> eyre::Result<()> {
+    let server = FlakyServer::start(&[(502, &[], ""), (404, &[], "")]);
+
+    let settings = Settings::resolve(
+        "test".to_string(),
+        Profile {
+            api_url: Some(server.url.clone()),
+            ..Profile::default()
+        },
+    )?;
+
+    let client = TrustblockClient::new(settings, "api_key")?.with_retry_policy(policy(1));
+
+    assert!(!block_on(client.report_exists("bafkreicid"))?);
+    assert_eq!(server.request_count(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use flate2::{write::GzEncoder, Compression};
+use mockito::Server;
+use semver::Version;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use trustblock_cli::{
+    cmd::block_on,
+    config::{Profile, Settings},
+    retry::RetryPolicy,
+    update::{extract_binary, Release, ReleaseSource, BINARY_NAME, TARGET},
+};
+
+fn source(server: &Server) -> eyre::Result<ReleaseSource> {
+    let mut settings = Settings::resolve(
+        "test".to_string(),
+        Profile {
+            release_url: Some(format!("{}/releases/", server.url())),
+            ..Profile::default()
+        },
+    )?;
+    settings.retry = RetryPolicy::never();
+
+    ReleaseSource::new(&settings)
+}
+
+/// Builds a cargo-dist like archive holding the binary in a top level folder.
+fn archive(binary: &[u8]) -> eyre::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(binary.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+
+    builder.append_data(
+        &mut header,
+        format!("trustblock-cli-v9.9.9-{TARGET}/{BINARY_NAME}"),
+        binary,
+    )?;
+
+    Ok(builder.into_inner()?.finish()?)
+}
+
+#[test]
+fn test_find_release() -> eyre::Result<()> {
+    let mut server = Server::new();
+
+    let archive_name = format!("trustblock-cli-v9.9.9-{TARGET}.tar.gz");
+
+    server
+        .mock("GET", "/releases/latest/download/dist-manifest.json")
+        .with_body(
+            json!({
+                "releases": [{
+                    "app_name": "trustblock-cli",
+                    "app_version": "9.9.9",
+                    "artifacts": ["trustblock-cli-v9.9.9-other-target.tar.gz", archive_name]
+                }],
+                "artifacts": {
+                    "trustblock-cli-v9.9.9-other-target.tar.gz": {
+                        "kind": "executable-zip",
+                        "target_triples": ["other-target"]
+                    },
+                    archive_name.clone(): {
+                        "kind": "executable-zip",
+                        "target_triples": [TARGET]
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .create();
+
+    server
+        .mock("GET", "/releases/download/v1.0.0/dist-manifest.json")
+        .with_status(404)
+        .create();
+
+    let source = source(&server)?;
+
+    let release = block_on(source.find(None))?;
+
+    let archive_url = format!("{}/releases/download/v9.9.9/{archive_name}", server.url());
+
+    assert_eq!(
+        release,
+        Release {
+            version: Version::new(9, 9, 9),
+            checksum_url: format!("{archive_url}.sha256"),
+            archive_url,
+        }
+    );
+
+    let error = block_on(source.find(Some(&Version::new(1, 0, 0)))).expect_err("no release");
+    assert!(error.to_string().starts_with("No release found"));
+
+    Ok(())
+}
+
+#[test]
+fn test_download_verifies_checksum() -> eyre::Result<()> {
+    let mut server = Server::new();
+
+    let release_archive = archive(b"new binary")?;
+    let checksum = format!("{:x}", Sha256::digest(&release_archive));
+
+    server
+        .mock("GET", "/releases/download/v9.9.9/archive.tar.gz")
+        .with_body(&release_archive)
+        .create();
+    server
+        .mock("GET", "/releases/download/v9.9.9/archive.tar.gz.sha256")
+        .with_body(format!("{checksum}  archive.tar.gz\n"))
+        .create();
+    server
+        .mock("GET", "/releases/download/v9.9.9/tampered.tar.gz")
+        .with_body(archive(b"tampered binary")?)
+        .create();
+    server
+        .mock("GET", "/releases/download/v9.9.9/tampered.tar.gz.sha256")
+        .with_body(&checksum)
+        .create();
+
+    let source = source(&server)?;
+
+    let release = |name: &str| {
+        let archive_url = format!("{}/releases/download/v9.9.9/{name}", server.url());
+
+        Release {
+            version: Version::new(9, 9, 9),
+            checksum_url: format!("{archive_url}.sha256"),
+            archive_url,
+        }
+    };
+
+    let downloaded = block_on(source.download(&release("archive.tar.gz")))?;
+    assert_eq!(extract_binary(&downloaded)?, b"new binary");
+
+    let error =
+        block_on(source.download(&release("tampered.tar.gz"))).expect_err("checksum mismatch");
+    assert!(error.to_string().starts_with("Checksum mismatch"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_replace_binary_rolls_back() -> eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    use trustblock_cli::update::replace_binary;
+
+    let dir = tempfile::TempDir::new()?;
+    let path = dir.path().join("trustblock");
+
+    let script = |version: &str| format!("#!/bin/sh\necho \"trustblock {version}\"\n");
+
+    std::fs::write(&path, script("0.9.1"))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+
+    // A binary reporting another version is rolled back
+    let error = replace_binary(&path, script("1.0.0").as_bytes(), &Version::new(9, 9, 9))
+        .expect_err("wrong version");
+
+    assert!(error.to_string().contains("restored"));
+    assert_eq!(std::fs::read_to_string(&path)?, script("0.9.1"));
+
+    replace_binary(&path, script("9.9.9").as_bytes(), &Version::new(9, 9, 9))?;
+
+    assert_eq!(std::fs::read_to_string(&path)?, script("9.9.9"));
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+
+    Ok(())
+}
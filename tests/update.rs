@@ -0,0 +1,96 @@
+use chrono::{Duration, Utc};
+use mockito::{Matcher, Server};
+use reqwest::Client;
+use serde_json::json;
+use serial_test::serial;
+use trustblock_cli::{
+    cmd::block_on,
+    config::{Profile, Settings},
+    update::{fetch_latest_version, is_disabled, newer_version, UpdateCache, NO_UPDATE_CHECK_VAR},
+};
+
+#[test]
+fn test_newer_version() {
+    assert_eq!(
+        newer_version("0.9.0", "0.8.3").map(|version| version.to_string()),
+        Some("0.9.0".to_string())
+    );
+    assert_eq!(
+        newer_version("0.10.0", "0.9.1").map(|version| version.to_string()),
+        Some("0.10.0".to_string())
+    );
+
+    // Older, equal and invalid versions do not prompt an update
+    assert_eq!(newer_version("0.8.2", "0.8.3"), None);
+    assert_eq!(newer_version("0.8.3", "0.8.3"), None);
+    assert_eq!(newer_version("0.9.0-beta.1", "0.9.0"), None);
+    assert_eq!(newer_version("latest", "0.8.3"), None);
+}
+
+#[test]
+fn test_cache_freshness() {
+    let now = Utc::now();
+
+    let cache = UpdateCache {
+        checked_at: now - Duration::hours(23),
+        latest_version: Some("0.9.0".to_string()),
+    };
+    assert!(cache.is_fresh(now));
+
+    let stale = UpdateCache {
+        checked_at: now - Duration::hours(25),
+        ..cache.clone()
+    };
+    assert!(!stale.is_fresh(now));
+
+    // A clock set back makes the cache stale instead of fresh forever
+    let future = UpdateCache {
+        checked_at: now + Duration::hours(1),
+        ..cache
+    };
+    assert!(!future.is_fresh(now));
+}
+
+#[test]
+fn test_fetch_latest_version() -> eyre::Result<()> {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/crates/trustblock-cli")
+        .match_header("user-agent", Matcher::Regex("^trustblock-cli/".to_string()))
+        .with_body(
+            json!({ "crate": { "newest_version": "1.0.0-rc.1", "max_stable_version": "0.9.0" } })
+                .to_string(),
+        )
+        .create();
+
+    let url = format!("{}/crates/trustblock-cli", server.url());
+    let version = block_on(fetch_latest_version(&Client::new(), &url))?;
+
+    mock.assert();
+    assert_eq!(version.to_string(), "0.9.0");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_update_check_opt_out() -> eyre::Result<()> {
+    let settings = Settings::resolve("test".to_string(), Profile::default())?;
+    assert!(!is_disabled(&settings));
+
+    let opted_out = Settings::resolve(
+        "test".to_string(),
+        Profile {
+            update_check: Some("false".to_string()),
+            ..Profile::default()
+        },
+    )?;
+    assert!(is_disabled(&opted_out));
+
+    std::env::set_var(NO_UPDATE_CHECK_VAR, "1");
+    assert!(is_disabled(&settings));
+    std::env::remove_var(NO_UPDATE_CHECK_VAR);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use common::constants::{AUDIT_FINDINGS_JSON_PATH, AUDIT_JSON_PATH};
+use serde_json::Value;
+use trustblock_cli::{
+    types::{
+        get_transformed_issues, Audit, Issue, IssueCount, Links, Severity, SeverityCount, Status,
+    },
+    utils::parse_json,
+    validation::{validate_audit, DEFAULT_MAX_ISSUES_PER_SEVERITY},
+};
+
+#[test]
+fn test_validate_audit_success() -> eyre::Result<()> {
+    let audit = parse_json::<Audit>(&AUDIT_JSON_PATH.into())?;
+
+    assert!(
+        validate_audit(&audit, DEFAULT_MAX_ISSUES_PER_SEVERITY).is_ok(),
+        "Audit data should be valid"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_audit_reports_all_errors() -> eyre::Result<()> {
+    let mut audit = parse_json::<Audit>(&AUDIT_JSON_PATH.into())?;
+
+    audit.project.name = "a".repeat(29);
+    audit.project.links = Links {
+        twitter: Some("not a url".to_string()),
+        ..audit.project.links
+    };
+    audit.project.contact.email = Some("not an email".to_string());
+    audit.issues.fixed.low = 51;
+
+    let errors = validate_audit(&audit, 50)
+        .expect_err("Audit data should be invalid")
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+
+    assert_eq!(
+        errors,
+        [
+            "issues.FIXED.LOW: must be at most 50",
+            "project.contact.email: invalid email",
+            "project.links.twitter: invalid url",
+            "project.name: length must be between 1 and 28",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_findings_derive_issue_counts() -> eyre::Result<()> {
+    let audit = parse_json::<Audit>(&AUDIT_FINDINGS_JSON_PATH.into())?;
+
+    assert!(
+        validate_audit(&audit, DEFAULT_MAX_ISSUES_PER_SEVERITY).is_ok(),
+        "Audit data should be valid"
+    );
+    assert_eq!(
+        audit.issues,
+        IssueCount::new(
+            SeverityCount::new(1, 0, 1, 0),
+            SeverityCount::new(1, 0, 0, 0)
+        )
+    );
+
+    // Findings are sent with the issues
+    let payload = serde_json::to_value(&audit)?;
+
+    assert_eq!(payload["issues"].as_array().map(Vec::len), Some(3));
+    assert_eq!(payload["findings"][0]["affected"][0]["startLine"], 120);
+
+    let mut json =
+        serde_json::from_str::<Value>(&std::fs::read_to_string(AUDIT_FINDINGS_JSON_PATH)?)?;
+
+    json["issues"] = serde_json::to_value(IssueCount::default())?;
+
+    let error = serde_json::from_value::<Audit>(json).expect_err("issues should not match");
+
+    assert!(error
+        .to_string()
+        .starts_with("`issues` do not match the 3 findings"));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_findings() -> eyre::Result<()> {
+    let mut audit = parse_json::<Audit>(&AUDIT_FINDINGS_JSON_PATH.into())?;
+
+    audit.findings[0].title = String::new();
+    audit.findings[0].affected[0].end_line = Some(100);
+
+    let errors = validate_audit(&audit, DEFAULT_MAX_ISSUES_PER_SEVERITY)
+        .expect_err("Audit data should be invalid")
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+
+    assert_eq!(
+        errors,
+        [
+            "findings[0].affected[0]: endLine must not be before startLine",
+            "findings[0].title: length must be between 1 and 200",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_informational_gas_and_new_statuses() -> eyre::Result<()> {
+    let mut json = serde_json::from_str::<Value>(&std::fs::read_to_string(AUDIT_JSON_PATH)?)?;
+
+    json["issues"]["ACKNOWLEDGED"] = serde_json::json!({ "GAS": 2, "INFORMATIONAL": 1 });
+    json["issues"]["UNRESOLVED"] = serde_json::json!({ "LOW": 1 });
+
+    let audit = serde_json::from_value::<Audit>(json)?;
+
+    assert_eq!(audit.issues.acknowledged.gas, 2);
+    assert_eq!(audit.issues.acknowledged.informational, 1);
+    assert_eq!(audit.issues.unresolved.low, 1);
+    assert_eq!(audit.issues.partially_fixed, SeverityCount::default());
+
+    let issues = get_transformed_issues(&audit.issues);
+
+    assert_eq!(
+        issues,
+        [
+            Issue::new(Status::Acknowledged, Severity::Gas),
+            Issue::new(Status::Acknowledged, Severity::Gas),
+            Issue::new(Status::Acknowledged, Severity::Informational),
+            Issue::new(Status::Unresolved, Severity::Low),
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(issues[3])?,
+        serde_json::json!({ "status": "UNRESOLVED", "severity": "LOW" })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_large_audits() -> eyre::Result<()> {
+    let mut audit = parse_json::<Audit>(&AUDIT_JSON_PATH.into())?;
+
+    audit.issues.fixed.low = 5000;
+    audit.issues.unresolved.gas = 2;
+
+    let errors = validate_audit(&audit, DEFAULT_MAX_ISSUES_PER_SEVERITY)
+        .expect_err("Audit data should be invalid")
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+
+    assert_eq!(errors, ["issues.FIXED.LOW: must be at most 1000"]);
+    assert!(validate_audit(&audit, 10_000).is_ok());
+
+    // Issues are sent as one entry per status and severity
+    assert_eq!(
+        serde_json::to_value(&audit)?["issues"],
+        serde_json::json!([
+            { "status": "FIXED", "severity": "LOW", "count": 5000 },
+            { "status": "UNRESOLVED", "severity": "GAS", "count": 2 },
+        ])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use serial_test::serial;
+use tempfile::TempDir;
+use trustblock_cli::{
+    error::ExitCode,
+    vault::{decrypt, encrypt, is_encrypted, new_passphrase, write_private},
+};
+
+#[test]
+fn test_encrypt_decrypt() -> eyre::Result<()> {
+    let encrypted = encrypt("some_api_key", "passphrase")?;
+
+    assert!(is_encrypted(&encrypted));
+    assert!(!encrypted.contains("some_api_key"));
+    assert_ne!(
+        encrypted,
+        encrypt("some_api_key", "passphrase")?,
+        "Salt and nonce should be random"
+    );
+
+    assert_eq!(decrypt(&encrypted, "passphrase")?, "some_api_key");
+
+    let error = decrypt(&encrypted, "wrong passphrase").expect_err("Passphrase is wrong");
+
+    assert_eq!(ExitCode::from_report(&error), ExitCode::Authentication);
+
+    assert!(decrypt("enc:v1:invalid", "passphrase").is_err());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_write_private_mode() -> eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new()?;
+    let path = dir.path().join("config.toml");
+
+    std::fs::write(&path, "")?;
+    write_private(&path, "api_key = \"\"")?;
+
+    assert_eq!(
+        std::fs::metadata(&path)?.permissions().mode() & 0o777,
+        0o600
+    );
+    assert_eq!(std::fs::read_to_string(&path)?, "api_key = \"\"");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_new_passphrase_from_env() -> eyre::Result<()> {
+    std::env::set_var("TRUSTBLOCK_PASSPHRASE", "");
+    let empty = new_passphrase();
+
+    std::env::set_var("TRUSTBLOCK_PASSPHRASE", "passphrase");
+    let passphrase = new_passphrase();
+
+    std::env::remove_var("TRUSTBLOCK_PASSPHRASE");
+
+    assert_eq!(
+        empty.expect_err("empty passphrase").to_string(),
+        "TRUSTBLOCK_PASSPHRASE can not be empty"
+    );
+    assert_eq!(passphrase?, "passphrase");
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use cid::{multibase::Base, Cid};
+use clap::Parser;
+use mockito::Server;
+use serial_test::serial;
+use trustblock_cli::{
+    cmd::{
+        block_on,
+        trustblock::{Cli, Commands},
+    },
+    error::ExitCode,
+    ipfs::file_cid,
+    shell::{CommandOutput, ResultStatus},
+};
+
+const REPORT_PDF: &str = "tests/test-data/report.pdf";
+
+fn verify(cid: &str) -> eyre::Result<CommandOutput> {
+    verify_with(&["--cid", cid])
+}
+
+fn verify_with(args: &[&str]) -> eyre::Result<CommandOutput> {
+    let cli = Cli::try_parse_from(
+        ["trustblock", "verify-report", "--pdf", REPORT_PDF]
+            .iter()
+            .chain(args),
+    )?;
+
+    let Commands::VerifyReport(cmd) = cli.command else {
+        panic!("Expected verify-report command");
+    };
+
+    block_on(cmd.run())
+}
+
+#[test]
+fn test_verify_report_match() -> eyre::Result<()> {
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    let output = verify(&cid)?;
+
+    assert_eq!(output.status, ResultStatus::Success);
+    assert_eq!(output.report_cid, Some(cid));
+    assert_eq!(output.exit_code, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_report_match_other_encodings() -> eyre::Result<()> {
+    let cid = Cid::try_from(file_cid(REPORT_PDF.as_ref())?)?;
+
+    for published_cid in [
+        Cid::new_v0(*cid.hash())?.to_string(),
+        cid.to_string_of_base(Base::Base58Btc)?,
+        cid.to_string_of_base(Base::Base36Lower)?,
+    ] {
+        let output = verify(&published_cid)?;
+
+        assert_eq!(output.status, ResultStatus::Success, "{published_cid}");
+    }
+
+    assert!(verify("not-a-cid").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_verify_report_fetches_from_store_gateway() -> eyre::Result<()> {
+    let mut server = Server::new();
+    let cid = file_cid(REPORT_PDF.as_ref())?;
+
+    let report = server
+        .mock("GET", format!("/ipfs/{cid}").as_str())
+        .with_body(std::fs::read(REPORT_PDF)?)
+        .create();
+
+    std::env::set_var("REPORT_STORE", "kubo");
+    std::env::set_var("IPFS_GATEWAY_URL", server.url());
+
+    let output = verify_with(&["--cid", &cid, "--fetch"]);
+
+    for var in ["REPORT_STORE", "IPFS_GATEWAY_URL"] {
+        std::env::remove_var(var);
+    }
+
+    let output = output?;
+
+    report.assert();
+
+    assert_eq!(output.status, ResultStatus::Success);
+    assert_eq!(
+        output.report_url,
+        Some(format!("{}/ipfs/{cid}", server.url()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_report_mismatch() -> eyre::Result<()> {
+    let output = verify("bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku")?;
+
+    assert_eq!(output.status, ResultStatus::Error);
+    assert_eq!(output.exit_code, Some(ExitCode::Mismatch as i32));
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_report_requires_hash() {
+    assert!(Cli::try_parse_from(["trustblock", "verify-report", "--pdf", REPORT_PDF]).is_err());
+
+    assert!(Cli::try_parse_from([
+        "trustblock",
+        "verify-report",
+        "--pdf",
+        REPORT_PDF,
+        "--cid",
+        "bafkrei",
+        "--audit-id",
+        "audit_id"
+    ])
+    .is_err());
+}